@@ -23,7 +23,8 @@ async fn main() {
         .auth(auth)
         .secure(true)
         .ssl(Ssl {
-            root_cert: Some(Ssl::read_pem(&"/path/root.pem").unwrap()),
+            root_certs: Ssl::read_pem_bundle(&"/path/root.pem").unwrap(),
+            ..Default::default()
         })
         .build()
         .unwrap();
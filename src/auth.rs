@@ -1,9 +1,42 @@
 use std::fmt;
+#[cfg(feature = "kerberos")]
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::WWW_AUTHENTICATE;
+use reqwest::{Response, Url};
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
 
 #[derive(Clone)]
 pub enum Auth {
     Basic(String, Option<String>),
     Jwt(String),
+    /// Trino's OAuth2 external authentication; see [`OAuth2`].
+    OAuth2(OAuth2),
+    /// A bearer token fetched from a [`TokenProvider`] for every request.
+    TokenProvider(Arc<dyn TokenProvider>),
+    /// Kerberos through SPNEGO (`Authorization: Negotiate`), using the system
+    /// GSSAPI library (MIT Kerberos' `libgssapi_krb5`).
+    ///
+    /// Each request carries a fresh token for the service principal
+    /// `{service_name}@{coordinator host}`. Credentials come from the default
+    /// credential cache unless `principal`, `keytab` or `ccache` (e.g.
+    /// `FILE:/tmp/krb5cc_trino`) are set; they are acquired once per
+    /// [`Client`](crate::client::Client) and acquired again when they stop
+    /// working.
+    #[cfg(feature = "kerberos")]
+    Kerberos {
+        service_name: String,
+        principal: Option<String>,
+        keytab: Option<PathBuf>,
+        ccache: Option<String>,
+    },
 }
 
 impl Auth {
@@ -14,6 +47,28 @@ impl Auth {
     pub fn new_jwt(token: impl ToString) -> Auth {
         Auth::Jwt(token.to_string())
     }
+
+    /// Kerberos with the default credential cache, for the service named
+    /// `service_name` (usually `trino`).
+    #[cfg(feature = "kerberos")]
+    pub fn new_kerberos(service_name: impl ToString) -> Auth {
+        Auth::Kerberos {
+            service_name: service_name.to_string(),
+            principal: None,
+            keytab: None,
+            ccache: None,
+        }
+    }
+
+    pub fn new_token_provider(provider: impl TokenProvider + 'static) -> Auth {
+        Auth::TokenProvider(Arc::new(provider))
+    }
+
+    /// OAuth2 external authentication, calling `redirect_handler` with the URL
+    /// the user must open to log in. See [`OAuth2`].
+    pub fn new_oauth2(redirect_handler: impl Fn(&Url) + Send + Sync + 'static) -> Auth {
+        Auth::OAuth2(OAuth2::new(redirect_handler))
+    }
 }
 
 impl fmt::Debug for Auth {
@@ -26,6 +81,289 @@ impl fmt::Debug for Auth {
                 .finish(),
 
             Auth::Jwt(_) => f.debug_struct("JwtAuth").field("token", &"******").finish(),
+
+            Auth::OAuth2(oauth) => f
+                .debug_struct("OAuth2Auth")
+                .field("timeout", &oauth.timeout)
+                .field("token", &"******")
+                .finish(),
+
+            Auth::TokenProvider(_) => f.debug_struct("TokenProviderAuth").finish_non_exhaustive(),
+
+            #[cfg(feature = "kerberos")]
+            Auth::Kerberos {
+                service_name,
+                principal,
+                keytab,
+                ccache,
+            } => f
+                .debug_struct("KerberosAuth")
+                .field("service_name", service_name)
+                .field("principal", principal)
+                .field("keytab", keytab)
+                .field("ccache", ccache)
+                .finish(),
+        }
+    }
+}
+
+/// A source of bearer tokens that may change over time, for use with
+/// [`Auth::TokenProvider`].
+///
+/// [`token`](TokenProvider::token) is called before every request, so the
+/// provider decides how long to cache a token. When the coordinator answers
+/// `401`, it is called once more and the request is replayed if it returned a
+/// different token, so short-lived tokens can rotate without rebuilding the
+/// [`Client`](crate::client::Client).
+///
+/// ```no_run
+/// # use futures::future::BoxFuture;
+/// # use trino_rust_client::auth::{Auth, BoxError, TokenProvider};
+/// /// Reads the token a sidecar keeps refreshed on disk.
+/// struct SidecarToken(std::path::PathBuf);
+///
+/// impl TokenProvider for SidecarToken {
+///     fn token(&self) -> BoxFuture<'_, Result<String, BoxError>> {
+///         Box::pin(async move {
+///             let token = std::fs::read_to_string(&self.0)?;
+///             Ok(token.trim().to_string())
+///         })
+///     }
+/// }
+///
+/// let auth = Auth::new_token_provider(SidecarToken("/var/run/secrets/trino-token".into()));
+/// ```
+pub trait TokenProvider: Send + Sync {
+    /// The token to send as `Authorization: Bearer <token>`.
+    fn token(&self) -> BoxFuture<'_, std::result::Result<String, BoxError>>;
+}
+
+/// The error a [`TokenProvider`] may fail with.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A source of SPNEGO tokens for `Authorization: Negotiate`, such as Kerberos.
+pub(crate) trait Negotiate: Send + Sync {
+    /// A fresh token for the coordinator at `host`.
+    fn token(&self, host: &str) -> BoxFuture<'_, Result<Vec<u8>>>;
+}
+
+/// Whether `resp` is a `401` asking for a (new) Negotiate token.
+pub(crate) fn is_negotiate_challenge(resp: &Response) -> bool {
+    resp.status() == reqwest::StatusCode::UNAUTHORIZED
+        && resp
+            .headers()
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .any(is_negotiate)
+}
+
+fn is_negotiate(header: &str) -> bool {
+    let scheme = header.trim().split(' ').next().unwrap_or_default();
+    scheme.eq_ignore_ascii_case("negotiate")
+}
+
+/// Trino's OAuth2 external authentication flow.
+///
+/// When the coordinator answers a request with `401` and a
+/// `WWW-Authenticate: Bearer x_redirect_server="...", x_token_server="..."`
+/// challenge, the client calls the redirect handler with the
+/// `x_redirect_server` URL (typically opening it in a browser or printing it),
+/// polls `x_token_server` until the login completes, and replays the request
+/// with the token it got back. The token is cached and sent with every later
+/// request (clones and [forks](crate::client::Client::fork) of the client share
+/// it) until the coordinator rejects it, which starts the flow again.
+///
+/// ```no_run
+/// # use trino_rust_client::{auth::Auth, client::ClientBuilder};
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new("analyst", "trino.example.com")
+///     .port(443)
+///     .secure(true)
+///     .auth(Auth::new_oauth2(|url| println!("Log in at {url}")))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct OAuth2 {
+    redirect_handler: Arc<dyn Fn(&Url) + Send + Sync>,
+    timeout: Duration,
+    token: Arc<RwLock<Option<String>>>,
+    // Held while a login is in progress, so concurrent requests that are
+    // challenged together share one login instead of each starting their own.
+    flow: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl OAuth2 {
+    pub fn new(redirect_handler: impl Fn(&Url) + Send + Sync + 'static) -> Self {
+        OAuth2 {
+            redirect_handler: Arc::new(redirect_handler),
+            timeout: Duration::from_secs(120),
+            token: Default::default(),
+            flow: Default::default(),
         }
     }
+
+    /// How long to wait for the user to complete the login. Defaults to 2
+    /// minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The cached token, if a login has completed.
+    pub fn token(&self) -> Option<String> {
+        self.token.read().unwrap().clone()
+    }
+
+    /// Run the external authentication flow for `challenge`, returning the new
+    /// token. `rejected` is the token the challenged
+    /// request was sent with: if another request has already replaced it, that
+    /// token is reused instead of logging in again.
+    pub(crate) async fn authenticate(
+        &self,
+        client: &reqwest::Client,
+        challenge: Challenge,
+        rejected: Option<String>,
+    ) -> Result<String> {
+        let _flow = self.flow.lock().await;
+        if let Some(token) = self.token().filter(|t| Some(t) != rejected.as_ref()) {
+            return Ok(token);
+        }
+        *self.token.write().unwrap() = None;
+
+        if let Some(url) = &challenge.redirect_server {
+            (self.redirect_handler)(url);
+        }
+        let token = self.poll_token(client, challenge.token_server).await?;
+        *self.token.write().unwrap() = Some(token.clone());
+        Ok(token)
+    }
+
+    async fn poll_token(&self, client: &reqwest::Client, mut url: Url) -> Result<String> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::ExternalAuth(
+                    "timed out waiting for the login to complete".to_string(),
+                ));
+            }
+            // The token server long-polls, answering once the login completes
+            // or with a `nextUri` to keep waiting on.
+            let resp = client.get(url).timeout(remaining).send().await?;
+            let status = resp.status();
+            if !status.is_success() {
+                let data = resp.text().await.unwrap_or_default();
+                return Err(Error::HttpNotOk(status, data));
+            }
+            let poll: TokenPoll = resp.json().await.map_err(|e| {
+                Error::ExternalAuth(format!("invalid token server response: {}", e))
+            })?;
+            match poll {
+                TokenPoll {
+                    token: Some(token), ..
+                } => return Ok(token),
+                TokenPoll {
+                    error: Some(error), ..
+                } => return Err(Error::ExternalAuth(error)),
+                TokenPoll {
+                    next_uri: Some(next),
+                    ..
+                } => {
+                    url = Url::parse(&next).map_err(|e| {
+                        Error::ExternalAuth(format!("invalid token server nextUri: {}", e))
+                    })?
+                }
+                _ => {
+                    return Err(Error::ExternalAuth(
+                        "token server returned neither a token nor a nextUri".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenPoll {
+    token: Option<String>,
+    #[serde(rename = "nextUri")]
+    next_uri: Option<String>,
+    error: Option<String>,
+}
+
+/// The `WWW-Authenticate: Bearer` challenge of an external authentication
+/// flow.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Challenge {
+    redirect_server: Option<Url>,
+    token_server: Url,
+}
+
+lazy_static! {
+    static ref CHALLENGE_PARAM: Regex = Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).unwrap();
+}
+
+impl Challenge {
+    /// The external authentication challenge of a `401` response, if any.
+    pub(crate) fn from_response(resp: &Response) -> Option<Challenge> {
+        resp.headers()
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find_map(Challenge::parse)
+    }
+
+    fn parse(header: &str) -> Option<Challenge> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return None;
+        }
+        let mut redirect_server = None;
+        let mut token_server = None;
+        for cap in CHALLENGE_PARAM.captures_iter(params) {
+            match &cap[1] {
+                "x_redirect_server" => redirect_server = Url::parse(&cap[2]).ok(),
+                "x_token_server" => token_server = Url::parse(&cap[2]).ok(),
+                _ => {}
+            }
+        }
+        Some(Challenge {
+            redirect_server,
+            token_server: token_server?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_challenge() {
+        let c = Challenge::parse(
+            r#"Bearer x_redirect_server="https://trino/oauth2/token/initiate/abc", x_token_server="https://trino/oauth2/token/abc""#,
+        )
+        .unwrap();
+        assert_eq!(
+            c.redirect_server.unwrap().as_str(),
+            "https://trino/oauth2/token/initiate/abc"
+        );
+        assert_eq!(c.token_server.as_str(), "https://trino/oauth2/token/abc");
+    }
+
+    #[test]
+    fn challenge_without_token_server_is_ignored() {
+        assert_eq!(Challenge::parse(r#"Bearer realm="trino""#), None);
+        assert_eq!(Challenge::parse(r#"Basic realm="trino""#), None);
+    }
+
+    #[test]
+    fn negotiate_challenge() {
+        assert!(is_negotiate("Negotiate"));
+        assert!(is_negotiate("negotiate YIIC..."));
+        assert!(!is_negotiate(r#"Bearer realm="trino""#));
+    }
 }
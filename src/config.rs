@@ -0,0 +1,255 @@
+//! Client configuration from environment variables and profile files, see
+//! [`ClientBuilder::from_env`] and [`ClientBuilder::from_config_file`]. Both
+//! build on the connection URL settings of [`dsn`](crate::dsn).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use url::Url;
+
+use crate::client::ClientBuilder;
+use crate::dsn::{normalize, Settings, KEYS};
+use crate::error::{Error, Result};
+
+pub(crate) fn from_env() -> Result<ClientBuilder> {
+    from_vars(std::env::vars())
+}
+
+fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Result<ClientBuilder> {
+    env_settings(vars)?.into_builder().map_err(in_config)
+}
+
+fn env_settings(vars: impl IntoIterator<Item = (String, String)>) -> Result<Settings> {
+    let mut vars: Vec<_> = vars
+        .into_iter()
+        .filter_map(|(name, v)| {
+            let key = normalize(name.strip_prefix("TRINO_")?);
+            KEYS.contains(&key.as_str()).then_some((key, name, v))
+        })
+        .collect();
+    // The environment has no order: `TRINO_URL` is the base, `TRINO_SERVER`
+    // overrides it and the individual variables override both. Spellings of
+    // the same key apply in name order.
+    vars.sort_by(|(a, a_name, _), (b, b_name, _)| {
+        let rank = |key: &str| match key {
+            "url" => 0,
+            "server" => 1,
+            _ => 2,
+        };
+        (rank(a), a_name).cmp(&(rank(b), b_name))
+    });
+    let mut settings = Settings::default();
+    for (key, _, value) in vars {
+        settings.push(&key, value).map_err(in_config)?;
+    }
+    Ok(settings)
+}
+
+pub(crate) fn from_config_file(path: &Path, profile: &str) -> Result<ClientBuilder> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| invalid(format!("cannot read {}: {}", path.display(), e)))?;
+    let mut profiles = parse_profiles(&text)?;
+    let base = profiles.remove("").unwrap_or_default();
+    let Some(selected) = profiles.remove(profile) else {
+        return Err(invalid(format!(
+            "no profile [{}] in {}",
+            profile,
+            path.display()
+        )));
+    };
+    let mut settings = Settings::default();
+    for (key, value) in base.into_iter().chain(selected) {
+        if !KEYS.contains(&key.as_str()) {
+            return Err(invalid(format!("unknown setting {}", key)));
+        }
+        settings.push(&key, value).map_err(in_config)?;
+    }
+    settings.into_builder().map_err(in_config)
+}
+
+/// `key = value` lines by `[profile]`; lines before the first profile are
+/// under `""`.
+fn parse_profiles(text: &str) -> Result<HashMap<String, Vec<(String, String)>>> {
+    let mut profiles: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    let mut current = String::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.trim().to_string();
+            profiles.entry(current.clone()).or_default();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(invalid(format!(
+                "line {}: expected key = value, got {}",
+                n + 1,
+                line
+            )));
+        };
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        profiles
+            .entry(current.clone())
+            .or_default()
+            .push((normalize(key.trim()), value.to_string()));
+    }
+    Ok(profiles)
+}
+
+impl Settings {
+    /// trino-cli's `server`: `host[:port]` or `http(s)://host[:port]`.
+    pub(crate) fn push_server(&mut self, server: &str) -> Result<()> {
+        let has_scheme = server.contains("://");
+        let url = match has_scheme {
+            true => server.to_string(),
+            false => format!("http://{}", server),
+        };
+        let url = Url::parse(&url).map_err(|e| invalid(format!("invalid server: {}", e)))?;
+        let ssl = match url.scheme() {
+            "http" => false,
+            "https" => true,
+            scheme => return Err(invalid(format!("invalid server scheme {}", scheme))),
+        };
+        let host = url
+            .host_str()
+            .ok_or_else(|| invalid("missing host".to_string()))?;
+        self.0.push(("host".to_string(), host.to_string()));
+        self.0.push(("ssl".to_string(), ssl.to_string()));
+        // An explicit `https://host` is on port 443, a bare `host` on 8080.
+        let port = match has_scheme {
+            true => url.port_or_known_default(),
+            false => url.port(),
+        };
+        if let Some(port) = port {
+            self.0.push(("port".to_string(), port.to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::InvalidConfig(msg)
+}
+
+/// The shared settings report [`Error::InvalidUrl`]; here they come from the
+/// environment or a profile file.
+fn in_config(err: Error) -> Error {
+    match err {
+        Error::InvalidUrl(msg) => Error::InvalidConfig(msg),
+        err => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(result: Result<ClientBuilder>) -> String {
+        match result {
+            Err(Error::InvalidConfig(msg)) => msg,
+            Err(e) => panic!("expected InvalidConfig, got {e:?}"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn env_vars_override_url() {
+        let mut settings = Settings::default();
+        settings
+            .push_url("trino://a@host:1/c1/s1?source=x")
+            .unwrap();
+        settings.push("catalog", "c2".to_string()).unwrap();
+        assert_eq!(settings.get("catalog"), Some("c2"));
+        assert_eq!(settings.get("schema"), Some("s1"));
+
+        assert!(from_vars(vars(&[
+            ("TRINO_CATALOG", "c2"),
+            ("TRINO_URL", "trino://a@host:1/c1"),
+            ("TRINO_HOME", "/opt/trino"),
+        ]))
+        .is_ok());
+        assert!(err(from_vars(vars(&[("TRINO_USER", "a")]))).contains("missing host"));
+        assert!(err(from_vars(vars(&[
+            ("TRINO_HOST", "host"),
+            ("TRINO_USER", "a"),
+            ("TRINO_PORT", "http"),
+        ])))
+        .contains("invalid port"));
+    }
+
+    #[test]
+    fn env_precedence_does_not_depend_on_order() {
+        let env = [
+            ("TRINO_PORT", "9000"),
+            ("TRINO_SERVER", "https://server.example.com:8443"),
+            ("TRINO_SSL", "false"),
+            ("TRINO_URL", "trino://a@url.example.com:1/c1"),
+            ("TRINO_HOST", "host.example.com"),
+        ];
+        let mut reversed = env;
+        reversed.reverse();
+        for env in [env, reversed] {
+            let settings = env_settings(vars(&env)).unwrap();
+            assert_eq!(settings.get("host"), Some("host.example.com"));
+            assert_eq!(settings.get("port"), Some("9000"));
+            assert_eq!(settings.get("ssl"), Some("false"));
+            assert_eq!(settings.get("catalog"), Some("c1"));
+        }
+
+        let server_over_url = env_settings(vars(&[
+            ("TRINO_SERVER", "https://server.example.com"),
+            ("TRINO_URL", "trino://a@url.example.com:1"),
+        ]))
+        .unwrap();
+        assert_eq!(server_over_url.get("host"), Some("server.example.com"));
+        assert_eq!(server_over_url.get("port"), Some("443"));
+        assert_eq!(server_over_url.get("ssl"), Some("true"));
+    }
+
+    #[test]
+    fn parses_profiles() {
+        let profiles = parse_profiles(
+            "# shared\nuser = alice\n\n[dev]\nserver = localhost:8080\n\n[prod]\nserver=\"https://trino.example.com\"\nsession-properties = a:1\n",
+        )
+        .unwrap();
+        assert_eq!(profiles[""], [("user".into(), "alice".into())]);
+        assert_eq!(
+            profiles["dev"],
+            [("server".into(), "localhost:8080".into())]
+        );
+        assert_eq!(
+            profiles["prod"],
+            [
+                ("server".into(), "https://trino.example.com".into()),
+                ("sessionproperties".into(), "a:1".into())
+            ]
+        );
+        assert!(matches!(
+            parse_profiles("[dev]\nserver"),
+            Err(Error::InvalidConfig(msg)) if msg.contains("line 2")
+        ));
+    }
+
+    #[test]
+    fn server_sets_host_port_and_ssl() {
+        let mut settings = Settings::default();
+        settings
+            .push("server", "https://trino.example.com:8443".to_string())
+            .unwrap();
+        assert_eq!(settings.get("host"), Some("trino.example.com"));
+        assert_eq!(settings.get("port"), Some("8443"));
+        assert_eq!(settings.get("ssl"), Some("true"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use reqwest::header::HeaderName;
 use reqwest::StatusCode;
 use serde::Deserialize;
@@ -16,6 +18,19 @@ pub enum Error {
     Forbidden { message: String },
     #[error("basic auth can not be used with http")]
     BasicAuthWithHttp,
+    /// The OAuth2 external authentication flow failed: the token server
+    /// reported an error, or the login did not complete in time.
+    #[error("external authentication failed: {0}")]
+    ExternalAuth(String),
+    /// The [`TokenProvider`](crate::auth::TokenProvider) failed to produce a
+    /// token.
+    #[error("token provider error: {0}")]
+    TokenProvider(#[source] crate::auth::BoxError),
+    /// Kerberos authentication failed, e.g. no valid ticket or keytab entry
+    /// for the principal.
+    #[cfg(feature = "kerberos")]
+    #[error("kerberos error: {0}")]
+    Kerberos(String),
     #[error("http error, reason: {0}")]
     HttpError(#[source] Box<reqwest::Error>),
     #[error("http not ok, code: {0}, reason: {1}")]
@@ -29,6 +44,9 @@ pub enum Error {
     /// Failed to decode or deserialize a response or a spooled segment.
     #[error("decode error: {0}")]
     Decode(String),
+    /// A value of a result failed to decode; the [`DecodeError`] says which.
+    #[error("decode error: {0}")]
+    RowDecode(Box<DecodeError>),
     /// Failed to load or read a TLS certificate.
     #[error("tls error: {0}")]
     Tls(String),
@@ -37,12 +55,32 @@ pub enum Error {
     /// data received without the `spooling` feature enabled).
     #[error("protocol error: {0}")]
     Protocol(String),
+    /// A value passed to [`Query::bind`](crate::query::Query::bind) cannot be
+    /// rendered as a SQL literal.
+    #[error("invalid bind parameter: {0}")]
+    InvalidParameter(String),
+    /// A redirect was not followed: it pointed to a host the
+    /// [`RedirectPolicy`](crate::redirect::RedirectPolicy) does not trust, or
+    /// there were too many of them.
+    #[error("redirect error: {0}")]
+    Redirect(String),
     #[error("inconsistent data")]
     InconsistentData,
     #[error("reach max attempt: {0}")]
     ReachMaxAttempt(usize),
     #[error("invalid host: {0}")]
     InvalidHost(String),
+    /// A connection URL passed to
+    /// [`ClientBuilder::from_url`](crate::client::ClientBuilder::from_url)
+    /// could not be parsed.
+    #[error("invalid connection url: {0}")]
+    InvalidUrl(String),
+    /// The environment or a profile file could not be turned into a client
+    /// configuration (see
+    /// [`ClientBuilder::from_env`](crate::client::ClientBuilder::from_env)),
+    /// or the builder's settings contradict each other.
+    #[error("invalid client configuration: {0}")]
+    InvalidConfig(String),
     /// An unexpected, internal failure that callers are not expected to handle.
     #[error("internal error: {0}")]
     InternalError(String),
@@ -67,8 +105,91 @@ impl From<QueryError> for Error {
     }
 }
 
+impl From<DecodeError> for Error {
+    fn from(err: DecodeError) -> Self {
+        Error::RowDecode(Box::new(err))
+    }
+}
+
+impl Error {
+    /// Locate a [`Error::RowDecode`] in the result page `page`.
+    pub(crate) fn at_page(mut self, page: Option<u64>) -> Self {
+        if let Error::RowDecode(err) = &mut self {
+            err.page = err.page.or(page);
+        }
+        self
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Where a value of a result failed to decode, and why.
+///
+/// Each location is known only where the value was decoded with it: a row
+/// of a [`DataSet`](crate::DataSet) knows its page and row, a column of a
+/// `#[derive(Trino)]` struct or a [`Row`](crate::Row) its name and types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecodeError {
+    /// The result page, as numbered by Trino in its `nextUri`, from 0.
+    pub page: Option<u64>,
+    /// The index of the row in its page.
+    pub row: Option<usize>,
+    /// The name of the column, or of the field of a nested row.
+    pub column: Option<String>,
+    /// The index of the column, or of the field of a nested row.
+    pub column_index: Option<usize>,
+    /// The Trino type of the column, e.g. `decimal(10,2)`.
+    pub trino_type: Option<String>,
+    /// The Rust type the column was decoded into.
+    pub rust_type: Option<&'static str>,
+    pub message: String,
+}
+
+impl DecodeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        DecodeError {
+            page: None,
+            row: None,
+            column: None,
+            column_index: None,
+            trino_type: None,
+            rust_type: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(page) = self.page {
+            write!(f, "page {}, ", page)?;
+        }
+        if let Some(row) = self.row {
+            write!(f, "row {}, ", row)?;
+        }
+        if let Some(column) = &self.column {
+            write!(f, "column {}", column)?;
+            let types = match (&self.trino_type, self.rust_type) {
+                (Some(trino), Some(rust)) => Some(format!("{} as {}", trino, rust)),
+                (Some(trino), None) => Some(trino.clone()),
+                (None, Some(rust)) => Some(rust.to_string()),
+                (None, None) => None,
+            };
+            match (self.column_index, types) {
+                (Some(index), Some(types)) => write!(f, " (#{}, {})", index, types)?,
+                (Some(index), None) => write!(f, " (#{})", index)?,
+                (None, Some(types)) => write!(f, " ({})", types)?,
+                (None, None) => {}
+            }
+            f.write_str(": ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
 #[derive(Debug, Deserialize)]
 pub struct TrinoRetryResult {
     pub id: String,
@@ -128,6 +249,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn decode_error_names_what_is_known() {
+        let mut err = DecodeError::new("boom");
+        assert_eq!(err.to_string(), "boom");
+        err.row = Some(7);
+        err.column = Some("price".into());
+        assert_eq!(err.to_string(), "row 7, column price: boom");
+        err.page = Some(2);
+        err.column_index = Some(3);
+        err.trino_type = Some("decimal(10,2)".into());
+        err.rust_type = Some("i64");
+        assert_eq!(
+            Error::from(err).to_string(),
+            "decode error: page 2, row 7, column price (#3, decimal(10,2) as i64): boom"
+        );
+    }
+
     #[test]
     fn query_error_preserves_source_chain() {
         use std::error::Error as _;
@@ -0,0 +1,232 @@
+//! Kerberos (SPNEGO) authentication through the system GSSAPI library, using
+//! the [`libgssapi`] bindings.
+//!
+//! Credentials come from the default credential cache unless
+//! [`Auth::Kerberos`] names a principal, a keytab or a credential cache. They
+//! are acquired once per client and reused until a token can no longer be made
+//! from them (e.g. the ticket expired).
+
+use std::ffi::CString;
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use libgssapi::context::{ClientCtx, CtxFlags};
+use libgssapi::credential::{Cred, CredUsage};
+use libgssapi::error::MajorFlags;
+use libgssapi::name::Name;
+use libgssapi::oid::{GSS_MECH_SPNEGO, GSS_NT_HOSTBASED_SERVICE, GSS_NT_KRB5_PRINCIPAL};
+
+use crate::auth::{Auth, Negotiate};
+use crate::error::{Error, Result};
+
+/// The [`Negotiate`] token source for [`Auth::Kerberos`].
+pub(crate) struct Kerberos(Arc<Settings>);
+
+struct Settings {
+    service_name: String,
+    principal: Option<String>,
+    keytab: Option<PathBuf>,
+    ccache: Option<String>,
+    cred: Mutex<Option<Cred>>,
+}
+
+impl Kerberos {
+    /// The token source for `auth`, if it is [`Auth::Kerberos`].
+    pub(crate) fn new(auth: &Auth) -> Option<Kerberos> {
+        let Auth::Kerberos {
+            service_name,
+            principal,
+            keytab,
+            ccache,
+        } = auth
+        else {
+            return None;
+        };
+        Some(Kerberos(Arc::new(Settings {
+            service_name: service_name.clone(),
+            principal: principal.clone(),
+            keytab: keytab.clone(),
+            ccache: ccache.clone(),
+            cred: Mutex::new(None),
+        })))
+    }
+}
+
+impl Negotiate for Kerberos {
+    fn token(&self, host: &str) -> BoxFuture<'_, Result<Vec<u8>>> {
+        let settings = self.0.clone();
+        let target = format!("{}@{}", settings.service_name, host);
+        Box::pin(async move {
+            // GSSAPI may talk to the KDC, so keep it off the async workers.
+            tokio::task::spawn_blocking(move || settings.token(&target))
+                .await
+                .map_err(|e| Error::InternalError(e.to_string()))?
+        })
+    }
+}
+
+impl Settings {
+    fn token(&self, target: &str) -> Result<Vec<u8>> {
+        let cached = self.cred.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some(token) = cached.and_then(|cred| first_token(cred, target).ok()) {
+            return Ok(token);
+        }
+        let cred = self.acquire()?;
+        *self.cred.lock().unwrap_or_else(|e| e.into_inner()) = Some(cred.clone());
+        first_token(cred, target)
+    }
+
+    fn acquire(&self) -> Result<Cred> {
+        if self.keytab.is_some() || self.ccache.is_some() {
+            return acquire_from(
+                self.principal.as_deref(),
+                self.keytab.as_deref(),
+                self.ccache.as_deref(),
+            );
+        }
+        let name = self
+            .principal
+            .as_deref()
+            .map(|p| Name::new(p.as_bytes(), Some(&GSS_NT_KRB5_PRINCIPAL)))
+            .transpose()
+            .map_err(|e| gss_error("invalid principal", e))?;
+        Cred::acquire(name.as_ref(), None, CredUsage::Initiate, None)
+            .map_err(|e| gss_error("cannot acquire credentials", e))
+    }
+}
+
+/// The SPNEGO token opening a security context with `target`
+/// (`service@host`).
+fn first_token(cred: Cred, target: &str) -> Result<Vec<u8>> {
+    let target = Name::new(target.as_bytes(), Some(&GSS_NT_HOSTBASED_SERVICE))
+        .map_err(|e| gss_error("invalid service name", e))?;
+    // Trino authenticates on this first token alone and never sends a reply
+    // token, so mutual authentication is not requested.
+    let mut ctx = ClientCtx::new(
+        Some(cred),
+        target,
+        CtxFlags::empty(),
+        Some(&GSS_MECH_SPNEGO),
+    );
+    match ctx.step(None, None) {
+        Ok(Some(token)) => Ok(token.to_vec()),
+        Ok(None) => Err(Error::Kerberos("no token to send".to_string())),
+        Err(e) => Err(gss_error("cannot initialize security context", e)),
+    }
+}
+
+/// Acquire initiator credentials from an explicit keytab and/or credential
+/// cache with MIT's `gss_acquire_cred_from`, which `libgssapi` does not wrap.
+fn acquire_from(
+    principal: Option<&str>,
+    keytab: Option<&std::path::Path>,
+    ccache: Option<&str>,
+) -> Result<Cred> {
+    use libgssapi_sys::{
+        gss_OID_set, gss_acquire_cred_from, gss_buffer_desc, gss_cred_id_t, gss_cred_usage_t,
+        gss_import_name, gss_key_value_element_desc, gss_key_value_set_desc, gss_name_t,
+        gss_release_name, OM_uint32, _GSS_C_INDEFINITE, GSS_C_INITIATE, GSS_S_COMPLETE,
+    };
+
+    fn c_string(what: &str, s: impl Into<Vec<u8>>) -> Result<CString> {
+        CString::new(s).map_err(|_| Error::Kerberos(format!("{} contains a NUL byte", what)))
+    }
+    fn status(context: &str, major: OM_uint32, minor: OM_uint32) -> Error {
+        gss_error(
+            context,
+            libgssapi::error::Error {
+                major: MajorFlags::from_bits_retain(major),
+                minor,
+            },
+        )
+    }
+
+    let keytab = keytab
+        .map(|k| c_string("keytab path", k.as_os_str().as_encoded_bytes()))
+        .transpose()?;
+    let ccache = ccache.map(|c| c_string("ccache", c)).transpose()?;
+    let mut elements = vec![];
+    if let Some(keytab) = &keytab {
+        elements.push(gss_key_value_element_desc {
+            key: c"client_keytab".as_ptr(),
+            value: keytab.as_ptr(),
+        });
+    }
+    if let Some(ccache) = &ccache {
+        elements.push(gss_key_value_element_desc {
+            key: c"ccache".as_ptr(),
+            value: ccache.as_ptr(),
+        });
+    }
+    let store = gss_key_value_set_desc {
+        count: elements.len() as OM_uint32,
+        elements: elements.as_mut_ptr(),
+    };
+
+    let mut minor: OM_uint32 = 0;
+    let mut name: gss_name_t = ptr::null_mut();
+    if let Some(principal) = principal {
+        let mut buf = gss_buffer_desc {
+            length: principal.len(),
+            value: principal.as_ptr() as *mut _,
+        };
+        // SAFETY: `buf` borrows `principal` for the duration of the call; a
+        // null name type lets the mechanism parse it as a Kerberos principal.
+        let major = unsafe { gss_import_name(&mut minor, &mut buf, ptr::null_mut(), &mut name) };
+        if major != GSS_S_COMPLETE {
+            return Err(status("invalid principal", major, minor));
+        }
+    }
+
+    let mut cred: gss_cred_id_t = ptr::null_mut();
+    // SAFETY: `store` and the strings it points to outlive the call; on
+    // success GSSAPI hands over ownership of `cred`, which `Cred` releases.
+    let major = unsafe {
+        gss_acquire_cred_from(
+            &mut minor,
+            name,
+            _GSS_C_INDEFINITE,
+            ptr::null_mut(),
+            GSS_C_INITIATE as gss_cred_usage_t,
+            &store,
+            &mut cred,
+            ptr::null_mut::<gss_OID_set>(),
+            ptr::null_mut(),
+        )
+    };
+    let acquired = if major == GSS_S_COMPLETE {
+        Ok(Cred::from(cred))
+    } else {
+        Err(status("cannot acquire credentials", major, minor))
+    };
+    if !name.is_null() {
+        let mut ignored: OM_uint32 = 0;
+        // SAFETY: `name` was imported above and is not used afterwards.
+        unsafe { gss_release_name(&mut ignored, &mut name) };
+    }
+    acquired
+}
+
+fn gss_error(context: &str, e: libgssapi::error::Error) -> Error {
+    Error::Kerberos(format!("{}: {}", context, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_credentials_are_reported() {
+        let kerberos = Kerberos::new(&Auth::Kerberos {
+            service_name: "trino".to_string(),
+            principal: Some("nobody@EXAMPLE.INVALID".to_string()),
+            keytab: Some("/nonexistent/trino.keytab".into()),
+            ccache: Some("MEMORY:trino-rust-client-test".to_string()),
+        })
+        .unwrap();
+        let err = kerberos.0.token("trino@localhost").unwrap_err();
+        assert!(matches!(err, Error::Kerberos(_)), "got {err:?}");
+    }
+}
@@ -60,6 +60,10 @@
 //!   (segments fetched from object storage), enabling
 //!   [`ClientBuilder::spooling_encoding`](client::ClientBuilder::spooling_encoding)
 //!   and related options.
+//! - `kerberos` — Kerberos (SPNEGO) authentication with
+//!   [`Auth::Kerberos`](auth::Auth::Kerberos), linking against the system
+//!   GSSAPI library (MIT Kerberos' `libgssapi_krb5`) through `libgssapi`,
+//!   whose bindings are generated at build time and need libclang.
 //!
 //! # Observability
 //!
@@ -75,13 +79,20 @@
 
 pub mod auth;
 pub mod client;
+mod config;
+mod dsn;
 pub mod error;
 
 mod header;
+#[cfg(feature = "kerberos")]
+mod kerberos;
 pub mod models;
+pub mod proxy;
+pub mod query;
 #[cfg(feature = "spooling")]
 pub mod spooling;
 
+pub mod redirect;
 pub mod retry;
 pub mod selected_role;
 pub mod session;
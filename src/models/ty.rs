@@ -3,7 +3,9 @@ use std::fmt;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The `rawType` of a type signature. Types this client does not know, such
+/// as those of connector plugins, are kept by name as `Other`.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum RawTrinoTy {
     BigInt,
     Integer,
@@ -32,13 +34,20 @@ pub enum RawTrinoTy {
     Json,
     IpAddress,
     Uuid,
+    Geometry,
+    SphericalGeography,
+    TDigest,
+    SetDigest,
+    Color,
+    ObjectId,
     Unknown,
+    Other(String),
 }
 
 impl RawTrinoTy {
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(&self) -> &str {
         use RawTrinoTy::*;
-        match *self {
+        match self {
             BigInt => "bigint",
             Integer => "integer",
             SmallInt => "smallint",
@@ -66,10 +75,19 @@ impl RawTrinoTy {
             Json => "json",
             IpAddress => "ipaddress",
             Uuid => "uuid",
+            Geometry => "Geometry",
+            SphericalGeography => "SphericalGeography",
+            TDigest => "tdigest",
+            SetDigest => "SetDigest",
+            Color => "color",
+            ObjectId => "ObjectId",
             Unknown => "unknown",
+            Other(name) => name,
         }
     }
 
+    /// The known type named `s`; see [`RawTrinoTy::from_name`] to keep unknown
+    /// names as `Other`.
     pub fn parse(s: &str) -> Option<Self> {
         use RawTrinoTy::*;
         let ty = match s {
@@ -100,11 +118,21 @@ impl RawTrinoTy {
             "json" => Json,
             "ipaddress" => IpAddress,
             "uuid" => Uuid,
+            "Geometry" => Geometry,
+            "SphericalGeography" => SphericalGeography,
+            "tdigest" => TDigest,
+            "SetDigest" => SetDigest,
+            "color" => Color,
+            "ObjectId" => ObjectId,
             "unknown" => Unknown,
             _ => return None,
         };
         Some(ty)
     }
+
+    pub fn from_name(s: &str) -> Self {
+        Self::parse(s).unwrap_or_else(|| RawTrinoTy::Other(s.to_string()))
+    }
 }
 
 impl Serialize for RawTrinoTy {
@@ -134,10 +162,7 @@ impl<'de> Deserialize<'de> for RawTrinoTy {
             where
                 E: de::Error,
             {
-                match RawTrinoTy::parse(v) {
-                    Some(d) => Ok(d),
-                    None => Err(E::custom(format!("invalid trino type: {}", v))),
-                }
+                Ok(RawTrinoTy::from_name(v))
             }
         }
 
@@ -170,8 +195,9 @@ mod tests {
         let ty = serde_json::from_str::<RawTrinoTy>(data).unwrap();
         assert_eq!(ty, RawTrinoTy::Json);
 
-        let invalid = "\"xxx\"";
-        let res = serde_json::from_str::<RawTrinoTy>(invalid);
-        assert!(res.is_err());
+        let unknown = "\"Bogus\"";
+        let ty = serde_json::from_str::<RawTrinoTy>(unknown).unwrap();
+        assert_eq!(ty, RawTrinoTy::Other("Bogus".to_string()));
+        assert_eq!(serde_json::to_string(&ty).unwrap(), unknown);
     }
 }
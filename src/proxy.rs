@@ -0,0 +1,69 @@
+//! HTTP(S) and SOCKS proxy settings, see [`ClientBuilder::proxy`].
+//!
+//! [`ClientBuilder::proxy`]: crate::client::ClientBuilder::proxy
+
+use std::fmt;
+
+use crate::error::Result;
+
+/// A proxy to send requests through.
+///
+/// The URL scheme selects the kind of proxy: `http://` or `https://` for an
+/// HTTP proxy (HTTPS targets are tunnelled with `CONNECT`), `socks5://` for a
+/// SOCKS5 proxy resolving host names locally, or `socks5h://` to let the proxy
+/// resolve them.
+#[derive(Clone)]
+pub struct Proxy {
+    url: String,
+    no_proxy: Option<String>,
+    basic_auth: Option<(String, String)>,
+}
+
+impl Proxy {
+    pub fn new(url: impl ToString) -> Self {
+        Proxy {
+            url: url.to_string(),
+            no_proxy: None,
+            basic_auth: None,
+        }
+    }
+
+    /// Hosts to reach directly, bypassing the proxy: a comma-separated list of
+    /// host names (`internal.example.com`), domain suffixes (`.example.com`),
+    /// IP addresses or CIDR blocks (`10.0.0.0/8`), as in the `NO_PROXY`
+    /// environment variable.
+    pub fn no_proxy(mut self, list: impl ToString) -> Self {
+        self.no_proxy = Some(list.to_string());
+        self
+    }
+
+    /// Authenticate to the proxy with HTTP basic auth.
+    pub fn basic_auth(mut self, username: impl ToString, password: impl ToString) -> Self {
+        self.basic_auth = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    pub(crate) fn to_reqwest(&self) -> Result<reqwest::Proxy> {
+        let mut proxy = reqwest::Proxy::all(&self.url)?;
+        if let Some((username, password)) = &self.basic_auth {
+            proxy = proxy.basic_auth(username, password);
+        }
+        if let Some(list) = &self.no_proxy {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(list));
+        }
+        Ok(proxy)
+    }
+}
+
+impl fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Proxy")
+            .field("url", &self.url)
+            .field("no_proxy", &self.no_proxy)
+            .field(
+                "basic_auth",
+                &self.basic_auth.as_ref().map(|(u, _)| (u, "******")),
+            )
+            .finish()
+    }
+}
@@ -0,0 +1,377 @@
+//! Parameterized queries.
+//!
+//! [`Client::query`] returns a [`Query`] whose `?` placeholders are bound with
+//! [`Query::bind`]. The SQL is sent to Trino as a prepared statement and run
+//! with `EXECUTE ... USING`, each bound value rendered as a typed SQL literal
+//! from its [`Trino::value`] and [`Trino::ty`], so callers never splice values
+//! into SQL by hand.
+//!
+//! The prepared statement travels in the `X-Trino-Prepared-Statement` header of
+//! the `EXECUTE` request only; the client's shared session is left untouched.
+
+use base64::Engine;
+use iterable::*;
+use serde_json::Value;
+
+use crate::client::{Client, ExecuteResult, RowStream, Statement};
+use crate::error::{Error, Result};
+use crate::session::QueryOptions;
+use crate::transaction::TransactionSlot;
+use crate::{DataSet, Trino, TrinoTy};
+
+/// A query with bound parameters, created by [`Client::query`].
+///
+/// Values are bound positionally, one per `?` placeholder. Placeholder count is
+/// checked by the coordinator when the statement is executed.
+pub struct Query<'a> {
+    client: &'a Client,
+    sql: String,
+    params: Vec<String>,
+    error: Option<Error>,
+    transaction: Option<TransactionSlot>,
+    options: Option<QueryOptions>,
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(client: &'a Client, sql: String) -> Self {
+        Query {
+            client,
+            sql,
+            params: vec![],
+            error: None,
+            transaction: None,
+            options: None,
+        }
+    }
+
+    pub(crate) fn in_transaction(mut self, slot: TransactionSlot) -> Self {
+        self.transaction = Some(slot);
+        self
+    }
+
+    /// Bind the next `?` placeholder to `value`.
+    ///
+    /// Any Rust type implementing [`Trino`] can be bound, including `Option<T>`
+    /// (rendered as `NULL` when `None`), `Vec<T>`, maps and `#[derive(Trino)]`
+    /// structs (rendered as a `ROW`). A value that cannot be rendered makes the
+    /// query fail with [`Error::InvalidParameter`] when it is run.
+    pub fn bind<T: Trino>(mut self, value: T) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        match to_literal(&value) {
+            Ok(lit) => self.params.push(lit),
+            Err(e) => {
+                let idx = self.params.len() + 1;
+                self.error = Some(Error::InvalidParameter(format!("parameter {idx}: {e}")));
+            }
+        }
+        self
+    }
+
+    /// Run the query with per-call session overrides; see [`QueryOptions`].
+    pub fn options(mut self, options: QueryOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Run the query and return the whole result set, like [`Client::get_all`].
+    pub async fn get_all<T>(self) -> Result<DataSet<T>>
+    where
+        T: Trino + 'static,
+        for<'de> T: serde::Deserialize<'de> + serde::Serialize,
+    {
+        let client = self.client;
+        client.get_all_statement(self.into_statement()?).await
+    }
+
+    /// Run the query and stream its rows lazily, like [`Client::stream`].
+    pub async fn stream<T>(self) -> Result<RowStream<'a, T>>
+    where
+        T: Trino + Send + 'static,
+        for<'de> T: serde::Deserialize<'de>,
+    {
+        let client = self.client;
+        client.stream_statement(self.into_statement()?).await
+    }
+
+    /// Run the statement, like [`Client::execute`].
+    pub async fn execute(self) -> Result<ExecuteResult> {
+        let client = self.client;
+        client.execute_statement(self.into_statement()?).await
+    }
+
+    fn into_statement(self) -> Result<Statement> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let name = format!("trino_rust_client_{}", uuid::Uuid::new_v4().simple());
+        let mut sql = format!("EXECUTE {}", name);
+        if !self.params.is_empty() {
+            sql.push_str(" USING ");
+            sql.push_str(&self.params.join(", "));
+        }
+        Ok(Statement {
+            sql,
+            prepared: Some((name, self.sql)),
+            transaction: self.transaction,
+            options: self.options,
+        })
+    }
+}
+
+/// Render `value` as a Trino SQL literal of type `T::ty()`.
+pub(crate) fn to_literal<T: Trino>(value: &T) -> std::result::Result<String, String> {
+    let json = serde_json::to_value(value.value()).map_err(|e| e.to_string())?;
+    literal(&T::ty(), &json)
+}
+
+fn literal(ty: &TrinoTy, value: &Value) -> std::result::Result<String, String> {
+    use TrinoTy::*;
+
+    // Map keys always reach us as JSON strings, so scalar types accept the
+    // string form too.
+    let text = || match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(format!(
+            "expected a {} value, got {}",
+            ty.full_type(),
+            other
+        )),
+    };
+
+    match (ty, value) {
+        (Option(_), Value::Null) => Ok("NULL".to_string()),
+        (Option(ty), value) => literal(ty, value),
+        (Defaulted(ty), value) | (Lenient(ty), value) => literal(ty, value),
+        (_, Value::Null) => Err(format!("null is not a valid {} value", ty.full_type())),
+        (Unknown, _) => Err("cannot bind a value of unknown Trino type".to_string()),
+        (ObjectId, _) | (Other(_), _) => Err(format!(
+            "cannot bind a value of Trino type {}",
+            ty.full_type()
+        )),
+        (Color, _) => Ok(format!("color({})", quote(&text()?))),
+        (Boolean, _) => unquoted::<bool>(ty, &text()?),
+        (TrinoInt(crate::TrinoInt::I32), _) => unquoted::<i32>(ty, &text()?),
+        (TrinoInt(_), _) | (TrinoFloat(_), _) => Ok(typed(ty, &text()?)),
+        (Varchar(_), _) => Ok(quote(&text()?)),
+        (Char(_), _) | (Decimal(_, _), _) => {
+            Ok(format!("CAST({} AS {})", quote(&text()?), ty.full_type()))
+        }
+        (AnyDecimal, _) | (Date, _) | (Uuid, _) | (IpAddress, _) => Ok(typed(ty, &text()?)),
+        (Time(_), _) | (TimeWithTimeZone(_), _) => Ok(format!("TIME {}", quote(&text()?))),
+        (Timestamp(_), _) | (TimestampWithTimeZone(_), _) => {
+            Ok(format!("TIMESTAMP {}", quote(&text()?)))
+        }
+        (IntervalYearToMonth, _) => Ok(interval(&text()?, "YEAR TO MONTH")),
+        (IntervalDayToSecond, _) => Ok(interval(&text()?, "DAY TO SECOND")),
+        (Json, value) => {
+            let s = serde_json::to_string(value).map_err(|e| e.to_string())?;
+            Ok(format!("JSON {}", quote(&s)))
+        }
+        (VarBinary, _) => binary(&text()?),
+        // Sketches are sent as their serialized bytes.
+        (HyperLogLog, _) | (P4HyperLogLog, _) | (QDigest(_), _) => {
+            Ok(format!("CAST({} AS {})", binary(&text()?)?, ty.full_type()))
+        }
+        (Geometry, _) => Ok(format!("ST_GeometryFromText({})", quote(&text()?))),
+        (SphericalGeography, _) => Ok(format!(
+            "to_spherical_geography(ST_GeometryFromText({}))",
+            quote(&text()?)
+        )),
+        (Array(ty), Value::Array(values)) => {
+            let elems = values.try_map(|v| literal(ty, v))?;
+            Ok(format!("ARRAY[{}]", elems.join(", ")))
+        }
+        (Map(kty, vty), Value::Object(entries)) => {
+            let mut keys = Vec::with_capacity(entries.len());
+            let mut values = Vec::with_capacity(entries.len());
+            for (k, v) in entries {
+                keys.push(literal(kty, &Value::String(k.clone()))?);
+                values.push(literal(vty, v)?);
+            }
+            Ok(format!(
+                "MAP(ARRAY[{}], ARRAY[{}])",
+                keys.join(", "),
+                values.join(", ")
+            ))
+        }
+        (Tuple(tys), Value::Array(values)) if tys.len() == values.len() => {
+            let fields = tys.lazy_zip(values).try_map(|(ty, v)| literal(ty, v))?;
+            Ok(format!(
+                "CAST(ROW({}) AS {})",
+                fields.join(", "),
+                ty.full_type()
+            ))
+        }
+        (Row(tys), Value::Array(values)) if tys.len() == values.len() => {
+            let fields = tys
+                .lazy_zip(values)
+                .try_map(|((_, ty), v)| literal(ty, v))?;
+            Ok(format!(
+                "CAST(ROW({}) AS {})",
+                fields.join(", "),
+                ty.full_type()
+            ))
+        }
+        (ty, other) => Err(format!(
+            "expected a {} value, got {}",
+            ty.full_type(),
+            other
+        )),
+    }
+}
+
+/// A `varbinary` literal of the base64 encoded bytes `s`.
+fn binary(s: &str) -> std::result::Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map_err(|e| e.to_string())?;
+    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    Ok(format!("X'{}'", hex))
+}
+
+/// A literal that is spliced in without quotes, so `s` is parsed and rendered
+/// again rather than trusted.
+fn unquoted<T>(ty: &TrinoTy, s: &str) -> std::result::Result<String, String>
+where
+    T: std::str::FromStr + std::fmt::Display,
+{
+    s.parse::<T>()
+        .map(|v| v.to_string())
+        .map_err(|_| format!("{:?} is not a valid {} value", s, ty.full_type()))
+}
+
+fn typed(ty: &TrinoTy, s: &str) -> String {
+    format!("{} {}", ty.full_type().to_uppercase(), quote(s))
+}
+
+fn interval(s: &str, qualifier: &str) -> String {
+    match s.strip_prefix('-') {
+        Some(abs) => format!("INTERVAL -{} {}", quote(abs), qualifier),
+        None => format!("INTERVAL {} {}", quote(s), qualifier),
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use super::{literal, to_literal};
+    use crate::{Decimal, QDigest, TrinoGeometry, TrinoTy, VarBinary};
+
+    #[test]
+    fn scalars() {
+        assert_eq!(to_literal(&true).unwrap(), "true");
+        assert_eq!(to_literal(&42_i32).unwrap(), "42");
+        assert_eq!(to_literal(&42_i64).unwrap(), "BIGINT '42'");
+        assert_eq!(to_literal(&1.5_f64).unwrap(), "DOUBLE '1.5'");
+        assert_eq!(to_literal(&None::<i32>).unwrap(), "NULL");
+        assert_eq!(to_literal(&Some(7_i32)).unwrap(), "7");
+    }
+
+    #[test]
+    fn strings_are_quoted_and_escaped() {
+        assert_eq!(
+            to_literal(&"O'Brien'; DROP TABLE t; --".to_string()).unwrap(),
+            "'O''Brien''; DROP TABLE t; --'"
+        );
+    }
+
+    #[test]
+    fn temporal_and_decimal_values_are_typed() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(to_literal(&date).unwrap(), "DATE '2024-02-29'");
+
+        let ts: NaiveDateTime = date.and_hms_milli_opt(1, 2, 3, 4).unwrap();
+        assert_eq!(
+            to_literal(&ts).unwrap(),
+            "TIMESTAMP '2024-02-29 01:02:03.004'"
+        );
+
+        let d: Decimal<10, 2> = "12.30".parse().unwrap();
+        assert_eq!(to_literal(&d).unwrap(), "CAST('12.30' AS decimal(10,2))");
+
+        let d: bigdecimal::BigDecimal = "-0.000123".parse().unwrap();
+        assert_eq!(to_literal(&d).unwrap(), "DECIMAL '-0.000123'");
+        let d: bigdecimal::BigDecimal = "1E-30".parse().unwrap();
+        assert_eq!(
+            to_literal(&d).unwrap(),
+            "DECIMAL '0.000000000000000000000000000001'"
+        );
+        let d: bigdecimal::BigDecimal = "-12345E+30".parse().unwrap();
+        assert_eq!(
+            to_literal(&d).unwrap(),
+            "DECIMAL '-12345000000000000000000000000000000'"
+        );
+        let d: Decimal<38, 30> = "0.000000000000000000000000000001".parse().unwrap();
+        assert_eq!(
+            to_literal(&d).unwrap(),
+            "CAST('0.000000000000000000000000000001' AS decimal(38,30))"
+        );
+    }
+
+    #[test]
+    fn varbinary_is_hex() {
+        assert_eq!(
+            to_literal(&VarBinary(vec![0, 1, 0xab])).unwrap(),
+            "X'0001AB'"
+        );
+    }
+
+    #[test]
+    fn geometry_is_wkt() {
+        let point: TrinoGeometry = "POINT (1 2)".parse().unwrap();
+        assert_eq!(
+            to_literal(&point).unwrap(),
+            "ST_GeometryFromText('POINT (1 2)')"
+        );
+    }
+
+    #[test]
+    fn sketches_are_cast_from_varbinary() {
+        let digest = QDigest::<i64>::from_bytes(vec![0, 1]);
+        assert_eq!(
+            to_literal(&digest).unwrap(),
+            "CAST(X'0001' AS qdigest(bigint))"
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(to_literal(&vec![1_i32, 2]).unwrap(), "ARRAY[1, 2]");
+        let m: BTreeMap<i32, String> = [(1, "a".to_string())].into_iter().collect();
+        assert_eq!(to_literal(&m).unwrap(), "MAP(ARRAY[1], ARRAY['a'])");
+    }
+
+    #[test]
+    fn unquoted_literals_are_parsed() {
+        use serde_json::json;
+
+        let int = TrinoTy::TrinoInt(crate::TrinoInt::I32);
+        assert_eq!(literal(&int, &json!("7")).unwrap(), "7");
+        assert!(literal(&int, &json!("1) OR (1=1")).is_err());
+        assert!(literal(&TrinoTy::Boolean, &json!("true OR 1=1")).is_err());
+        assert_eq!(
+            literal(&TrinoTy::Boolean, &json!("false")).unwrap(),
+            "false"
+        );
+
+        // map keys reach the literal as strings
+        let m: BTreeMap<bool, i32> = [(true, 1)].into_iter().collect();
+        assert_eq!(to_literal(&m).unwrap(), "MAP(ARRAY[true], ARRAY[1])");
+    }
+
+    #[test]
+    fn nan_is_rejected() {
+        assert!(to_literal(&f64::NAN).is_err());
+    }
+}
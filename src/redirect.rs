@@ -0,0 +1,164 @@
+use std::future::Future;
+
+use reqwest::header::LOCATION;
+use reqwest::{RequestBuilder, Response, StatusCode, Url};
+
+use crate::error::{Error, Result};
+
+/// Controls which redirects the client follows, e.g. from a
+/// [Trino Gateway](https://trinodb.github.io/trino-gateway/) or load balancer
+/// in front of the coordinators.
+///
+/// Only `307 Temporary Redirect` and `308 Permanent Redirect` are followed:
+/// they preserve the method and body, so the statement of a
+/// `POST /v1/statement` is re-sent as is. Other redirects would turn it into a
+/// `GET` without the statement, and are returned as errors instead.
+///
+/// A redirect re-sends the request body and the credentials, so it is only
+/// followed to the host the request was sent to or to one of `trusted_hosts`,
+/// and never from `https` to `http`; any other redirect fails with
+/// [`Error::Redirect`]. Once a query is submitted, its result pages are
+/// fetched from the `nextUri` returned by the coordinator that accepted it,
+/// not through the gateway.
+///
+/// ```
+/// # use trino_rust_client::redirect::RedirectPolicy;
+/// let policy = RedirectPolicy {
+///     trusted_hosts: vec![".trino.example.com".to_string()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct RedirectPolicy {
+    /// Maximum number of redirects to follow for one request; `0` disables
+    /// following redirects.
+    pub max_redirects: usize,
+    /// Other hosts that may receive redirected requests: host names
+    /// (`trino-1.example.com`) or, with a leading dot, domain suffixes
+    /// (`.example.com`).
+    pub trusted_hosts: Vec<String>,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            max_redirects: 10,
+            trusted_hosts: Vec::new(),
+        }
+    }
+}
+
+impl RedirectPolicy {
+    /// Whether a request first sent to `origin` may be redirected to `target`.
+    fn is_trusted(&self, origin: &Url, target: &Url) -> bool {
+        if origin.scheme() == "https" && target.scheme() != "https" {
+            return false;
+        }
+        let Some(host) = target.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        origin
+            .host_str()
+            .is_some_and(|origin| origin.eq_ignore_ascii_case(&host))
+            || self.trusted_hosts.iter().any(|trusted| {
+                let trusted = trusted.to_ascii_lowercase();
+                match trusted.strip_prefix('.') {
+                    Some(domain) => host == domain || host.ends_with(&trusted),
+                    None => host == trusted,
+                }
+            })
+    }
+
+    /// Send `req` with `send`, following the redirects this policy allows.
+    /// `send` is called with each request before credentials are added, so
+    /// they are added afresh for every host.
+    pub(crate) async fn send<F, Fut>(&self, req: RequestBuilder, mut send: F) -> Result<Response>
+    where
+        F: FnMut(RequestBuilder) -> Fut,
+        Fut: Future<Output = Result<Response>>,
+    {
+        let mut req = req;
+        let mut origin = None;
+        for _ in 0..=self.max_redirects {
+            let replay = req.try_clone();
+            let resp = send(req).await?;
+            let first_url = origin.get_or_insert_with(|| resp.url().clone());
+            let (Some(target), Some(replay)) = (redirect_target(&resp), replay) else {
+                return Ok(resp);
+            };
+            let target = target?;
+            if !self.is_trusted(first_url, &target) {
+                return Err(Error::Redirect(format!(
+                    "refusing to follow redirect to untrusted location {}",
+                    target
+                )));
+            }
+            let (client, replay) = replay.build_split();
+            let mut replay = replay?;
+            *replay.url_mut() = target;
+            req = RequestBuilder::from_parts(client, replay);
+        }
+        Err(Error::Redirect(format!(
+            "more than {} redirects",
+            self.max_redirects
+        )))
+    }
+}
+
+/// Where a `307` / `308` response redirects to.
+fn redirect_target(resp: &Response) -> Option<Result<Url>> {
+    if !matches!(
+        resp.status(),
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return None;
+    }
+    let location = resp.headers().get(LOCATION)?;
+    Some(
+        location
+            .to_str()
+            .ok()
+            .and_then(|location| resp.url().join(location).ok())
+            .ok_or_else(|| Error::Redirect(format!("invalid Location header {:?}", location))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted(policy: &RedirectPolicy, origin: &str, target: &str) -> bool {
+        policy.is_trusted(&origin.parse().unwrap(), &target.parse().unwrap())
+    }
+
+    #[test]
+    fn trusts_origin_and_listed_hosts() {
+        let policy = RedirectPolicy {
+            trusted_hosts: vec!["trino-1.internal".into(), ".example.com".into()],
+            ..Default::default()
+        };
+        let origin = "https://gateway.local/v1/statement";
+        assert!(trusted(&policy, origin, "https://GATEWAY.local:8443/x"));
+        assert!(trusted(&policy, origin, "https://trino-1.internal/x"));
+        assert!(trusted(&policy, origin, "https://a.b.example.com/x"));
+        assert!(trusted(&policy, origin, "https://example.com/x"));
+        assert!(!trusted(&policy, origin, "https://evil-example.com/x"));
+        assert!(!trusted(&policy, origin, "https://trino-2.internal/x"));
+    }
+
+    #[test]
+    fn rejects_downgrade_to_http() {
+        let policy = RedirectPolicy::default();
+        assert!(!trusted(
+            &policy,
+            "https://gateway.local/v1/statement",
+            "http://gateway.local/v1/statement"
+        ));
+        assert!(trusted(
+            &policy,
+            "http://gateway.local/v1/statement",
+            "https://gateway.local/v1/statement"
+        ));
+    }
+}
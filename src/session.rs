@@ -14,7 +14,7 @@ fn default<T: Default>() -> T {
     Default::default()
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Session {
     pub url: Url,
     pub user: String,
@@ -125,3 +125,105 @@ impl SessionBuilder {
         Ok(ret)
     }
 }
+
+/// Session settings for a single call, layered over the [`Client`](crate::Client)'s
+/// shared session without modifying it.
+///
+/// Pass to [`Client::get_all_with`](crate::Client::get_all_with),
+/// [`stream_with`](crate::Client::stream_with),
+/// [`execute_with`](crate::Client::execute_with) or
+/// [`Query::options`](crate::query::Query::options). Unset fields fall back to
+/// the client's session; session properties and roles are merged key by key,
+/// and client tags are added to the client's.
+///
+/// A statement run with options is isolated: session changes it returns
+/// (`USE`, `SET SESSION`, `SET ROLE`, `PREPARE`, …) are not written back to
+/// the shared session, so concurrent calls on one client never observe each
+/// other's settings. Put such settings in the options instead.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use trino_rust_client::session::QueryOptions;
+/// let opts = QueryOptions::new()
+///     .catalog("iceberg")
+///     .schema("tenant_42")
+///     .property("query_max_run_time", "5m")
+///     .client_tag("dashboard")
+///     .timeout(Duration::from_secs(60));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct QueryOptions {
+    catalog: Option<String>,
+    schema: Option<String>,
+    source: Option<String>,
+    properties: HashMap<String, String>,
+    client_tags: HashSet<String>,
+    roles: HashMap<String, SelectedRole>,
+    timeout: Option<Duration>,
+}
+
+impl QueryOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn catalog(mut self, s: impl ToString) -> Self {
+        self.catalog = Some(s.to_string());
+        self
+    }
+
+    pub fn schema(mut self, s: impl ToString) -> Self {
+        self.schema = Some(s.to_string());
+        self
+    }
+
+    pub fn source(mut self, s: impl ToString) -> Self {
+        self.source = Some(s.to_string());
+        self
+    }
+
+    pub fn property(mut self, k: impl ToString, v: impl ToString) -> Self {
+        self.properties.insert(k.to_string(), v.to_string());
+        self
+    }
+
+    pub fn client_tag(mut self, s: impl ToString) -> Self {
+        self.client_tags.insert(s.to_string());
+        self
+    }
+
+    /// Set the role for `catalog` (use `"system"` for the system role).
+    pub fn role(mut self, catalog: impl ToString, role: SelectedRole) -> Self {
+        self.roles.insert(catalog.to_string(), role);
+        self
+    }
+
+    /// Timeout for each HTTP request of the call, overriding
+    /// [`ClientBuilder::client_request_timeout`](crate::ClientBuilder::client_request_timeout).
+    pub fn timeout(mut self, d: Duration) -> Self {
+        self.timeout = Some(d);
+        self
+    }
+
+    pub(crate) fn request_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub(crate) fn apply(&self, session: &mut Session) {
+        if let Some(v) = &self.catalog {
+            session.catalog = Some(v.clone());
+        }
+        if let Some(v) = &self.schema {
+            session.schema = Some(v.clone());
+        }
+        if let Some(v) = &self.source {
+            session.source = v.clone();
+        }
+        if let Some(v) = self.timeout {
+            session.client_request_timeout = v;
+        }
+        session.properties.extend(self.properties.clone());
+        session.client_tags.extend(self.client_tags.clone());
+        session.roles.extend(self.roles.clone());
+    }
+}
@@ -19,6 +19,7 @@ fn default_max_concurrent_segments() -> usize {
 }
 
 // Fetcher for segments with the spooling protocol
+#[derive(Clone)]
 pub struct SegmentFetcher {
     http_client: Client,
     max_concurrent_segments: usize,
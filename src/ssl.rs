@@ -1,49 +1,282 @@
-use crate::error::Result;
 use std::io::Read;
 use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, RootCertStore, SignatureScheme};
+
+use crate::error::{Error, Result};
 
 #[derive(Clone)]
-pub struct Certificate(pub(crate) reqwest::Certificate);
+pub struct Certificate(pub(crate) CertificateDer<'static>);
+
+/// A client certificate and its private key, presented for mutual TLS.
+#[derive(Clone)]
+pub struct Identity {
+    pub(crate) inner: reqwest::Identity,
+    pem: Vec<u8>,
+}
+
+/// The minimum TLS protocol version to accept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    Tls12,
+    Tls13,
+}
 
 #[derive(Clone)]
 pub struct Ssl {
-    pub root_cert: Option<Certificate>,
+    /// Extra root certificates to trust, e.g. a corporate CA chain read with
+    /// [`Ssl::read_pem_bundle`].
+    pub root_certs: Vec<Certificate>,
+    /// Whether to also trust the platform's root certificates (the default).
+    /// When `false`, only `root_certs` are trusted by the coordinator
+    /// connection; spooled segment storage always trusts the platform roots.
+    pub built_in_roots: bool,
+    /// Client certificate presented to the coordinator (mutual TLS). It is
+    /// not presented to spooled segment storage.
+    pub client_identity: Option<Identity>,
+    pub min_tls_version: Option<TlsVersion>,
+    /// Base64 SHA-256 digests of the DER subject public key info (SPKI) the
+    /// server is pinned to, in addition to the usual certificate validation.
+    /// A connection succeeds when the key of the server's own certificate
+    /// matches any pin; CA and intermediate keys cannot be pinned, as the
+    /// server may send certificates that are not part of the verified chain.
+    /// Pins only apply to the coordinator, not to spooled
+    /// segment storage. Compute a pin with
+    /// `openssl x509 -in cert.pem -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64`.
+    pub spki_pins: Vec<String>,
+    /// Whether the server certificate must be issued for the host connected
+    /// to (the default). When `false`, the certificate chain is still
+    /// validated but any host name is accepted, like the JDBC driver's
+    /// `SSLVerification=CA`.
+    pub verify_hostname: bool,
 }
 
 impl Default for Ssl {
     fn default() -> Self {
-        Ssl { root_cert: None }
+        Ssl {
+            root_certs: vec![],
+            built_in_roots: true,
+            client_identity: None,
+            min_tls_version: None,
+            spki_pins: vec![],
+            verify_hostname: true,
+        }
     }
 }
 
 impl Ssl {
+    /// Read a PEM file holding a single certificate. Use
+    /// [`read_pem_bundle`](Ssl::read_pem_bundle) for a file with several.
     pub fn read_pem<P: AsRef<Path>>(root_certificate_path: &P) -> Result<Certificate> {
-        let buf = Self::read_file(&root_certificate_path)?;
-        match reqwest::Certificate::from_pem(&buf) {
-            Ok(cert) => Ok(Certificate(cert)),
-            Err(e) => Err(crate::error::Error::Tls(format!(
-                "Cannot load PEM certificate {:?}",
-                e
-            ))),
+        let mut certs = Self::read_pem_bundle(root_certificate_path)?;
+        if certs.len() > 1 {
+            return Err(Error::Tls(format!(
+                "{} holds {} certificates, use Ssl::read_pem_bundle",
+                root_certificate_path.as_ref().display(),
+                certs.len()
+            )));
+        }
+        Ok(certs.remove(0))
+    }
+
+    /// Read every certificate of a PEM bundle, e.g. a CA chain with
+    /// intermediate and cross-signed roots.
+    pub fn read_pem_bundle<P: AsRef<Path>>(path: &P) -> Result<Vec<Certificate>> {
+        let buf = Self::read_file(&path)?;
+        let certs = CertificateDer::pem_slice_iter(&buf)
+            .map(|cert| cert.map(Certificate))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Tls(format!("Cannot load PEM certificate {:?}", e)))?;
+        if certs.is_empty() {
+            return Err(Error::Tls(format!(
+                "No PEM certificate found in {}",
+                path.as_ref().display()
+            )));
         }
+        Ok(certs)
     }
 
     pub fn read_der<P: AsRef<Path>>(root_certificate_path: &P) -> Result<Certificate> {
         let buf = Self::read_file(&root_certificate_path)?;
         match reqwest::Certificate::from_der(&buf) {
-            Ok(cert) => Ok(Certificate(cert)),
-            Err(e) => Err(crate::error::Error::Tls(format!(
-                "Cannot load DER certificate {:?}",
+            Ok(_) => Ok(Certificate(CertificateDer::from(buf))),
+            Err(e) => Err(Error::Tls(format!("Cannot load DER certificate {:?}", e))),
+        }
+    }
+
+    /// Load a client identity from a PEM certificate (optionally followed by
+    /// its chain) and a PEM private key (PKCS#8, PKCS#1 or SEC1). Use
+    /// [`read_identity_pkcs12`](Ssl::read_identity_pkcs12) for a `.p12` /
+    /// `.pfx` bundle.
+    pub fn read_identity_pem<P: AsRef<Path>, K: AsRef<Path>>(
+        cert_path: &P,
+        key_path: &K,
+    ) -> Result<Identity> {
+        let mut pem = Self::read_file(&cert_path)?;
+        if key_path.as_ref() != cert_path.as_ref() {
+            pem.push(b'\n');
+            pem.extend(Self::read_file(&key_path)?);
+        }
+        match reqwest::Identity::from_pem(&pem) {
+            Ok(inner) => Ok(Identity { inner, pem }),
+            Err(e) => Err(Error::Tls(format!(
+                "Cannot load PEM client identity {:?}",
                 e
             ))),
         }
     }
 
+    /// Load a client identity from a PKCS#12 (`.p12` / `.pfx`) bundle
+    /// protected by `password`. The first private key and its certificate
+    /// chain are used.
+    pub fn read_identity_pkcs12<P: AsRef<Path>>(path: &P, password: &str) -> Result<Identity> {
+        let buf = Self::read_file(&path)?;
+        let store = p12_keystore::KeyStore::from_pkcs12(&buf, password)
+            .map_err(|e| Error::Tls(format!("Cannot load PKCS#12 client identity {}", e)))?;
+        let (_, chain) = store.private_key_chain().ok_or_else(|| {
+            Error::Tls(format!(
+                "No private key found in {}",
+                path.as_ref().display()
+            ))
+        })?;
+
+        // The TLS backend only takes PEM identities.
+        let mut pem = String::new();
+        for cert in chain.chain() {
+            pem.push_str(&pem_block("CERTIFICATE", cert.as_der()));
+        }
+        pem.push_str(&pem_block("PRIVATE KEY", chain.key()));
+        let pem = pem.into_bytes();
+        match reqwest::Identity::from_pem(&pem) {
+            Ok(inner) => Ok(Identity { inner, pem }),
+            Err(e) => Err(Error::Tls(format!(
+                "Cannot load PKCS#12 client identity {:?}",
+                e
+            ))),
+        }
+    }
+
+    /// The settings for spooled segment downloads. Object storage is not the
+    /// coordinator: it keeps the extra roots and the minimum TLS version, but
+    /// not the coordinator's pins or client identity, and the platform roots
+    /// stay trusted.
+    #[cfg(feature = "spooling")]
+    pub(crate) fn for_segments(&self) -> Ssl {
+        Ssl {
+            root_certs: self.root_certs.clone(),
+            min_tls_version: self.min_tls_version,
+            ..Default::default()
+        }
+    }
+
+    /// Apply these settings to the HTTP client being built.
+    pub(crate) fn configure(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder> {
+        if !self.spki_pins.is_empty() || !self.verify_hostname {
+            // Pinning and skipping the host name check need a certificate
+            // verifier of our own, so the whole TLS configuration is built
+            // here instead of by reqwest.
+            return Ok(builder.tls_backend_preconfigured(self.custom_tls_config()?));
+        }
+
+        let roots = self
+            .root_certs
+            .iter()
+            .map(|cert| reqwest::Certificate::from_der(&cert.0))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        builder = if self.built_in_roots {
+            builder.tls_certs_merge(roots)
+        } else {
+            builder.tls_certs_only(roots)
+        };
+        if let Some(identity) = &self.client_identity {
+            builder = builder.identity(identity.inner.clone());
+        }
+        if let Some(version) = self.min_tls_version {
+            builder = builder.tls_version_min(match version {
+                TlsVersion::Tls12 => reqwest::tls::Version::TLS_1_2,
+                TlsVersion::Tls13 => reqwest::tls::Version::TLS_1_3,
+            });
+        }
+        Ok(builder)
+    }
+
+    fn custom_tls_config(&self) -> Result<rustls::ClientConfig> {
+        let tls_err = |e: rustls::Error| Error::Tls(e.to_string());
+        let pins = self
+            .spki_pins
+            .iter()
+            .map(|pin| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(pin)
+                    .ok()
+                    .filter(|digest| digest.len() == 32)
+                    .ok_or_else(|| {
+                        Error::Tls(format!("SPKI pin {:?} is not a base64 SHA-256 digest", pin))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let provider = CryptoProvider::get_default()
+            .cloned()
+            .unwrap_or_else(|| Arc::new(rustls::crypto::aws_lc_rs::default_provider()));
+        let roots = self.root_certs.iter().map(|cert| cert.0.clone());
+        let inner: Arc<dyn ServerCertVerifier> = if self.built_in_roots {
+            Arc::new(
+                rustls_platform_verifier::Verifier::new_with_extra_roots(roots, provider.clone())
+                    .map_err(tls_err)?,
+            )
+        } else {
+            let mut store = RootCertStore::empty();
+            store.add_parsable_certificates(roots);
+            WebPkiServerVerifier::builder_with_provider(Arc::new(store), provider.clone())
+                .build()
+                .map_err(|e| Error::Tls(e.to_string()))?
+        };
+
+        let versions: &[&rustls::SupportedProtocolVersion] = match self.min_tls_version {
+            Some(TlsVersion::Tls13) => &[&rustls::version::TLS13],
+            _ => rustls::ALL_VERSIONS,
+        };
+        let builder = rustls::ClientConfig::builder_with_provider(provider)
+            .with_protocol_versions(versions)
+            .map_err(tls_err)?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(Verifier {
+                inner,
+                pins,
+                verify_hostname: self.verify_hostname,
+            }));
+
+        let mut config = match &self.client_identity {
+            Some(identity) => {
+                let certs = CertificateDer::pem_slice_iter(&identity.pem)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| Error::Tls(format!("Cannot load PEM client identity {:?}", e)))?;
+                let key = PrivateKeyDer::from_pem_slice(&identity.pem)
+                    .map_err(|e| Error::Tls(format!("Cannot load PEM client identity {:?}", e)))?;
+                builder.with_client_auth_cert(certs, key).map_err(tls_err)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(config)
+    }
+
     fn read_file<P: AsRef<Path>>(file_path: &P) -> Result<Vec<u8>> {
         let mut buf = Vec::new();
         std::fs::File::open(file_path)
             .map_err(|e| {
-                crate::error::Error::Tls(format!(
+                Error::Tls(format!(
                     "Error opening file {}. {}",
                     file_path.as_ref().display(),
                     e
@@ -51,7 +284,7 @@ impl Ssl {
             })?
             .read_to_end(&mut buf)
             .map_err(|e| {
-                crate::error::Error::Tls(format!(
+                Error::Tls(format!(
                     "Error reading file {}. {}",
                     file_path.as_ref().display(),
                     e
@@ -61,3 +294,146 @@ impl Ssl {
         Ok(buf)
     }
 }
+
+fn pem_block(label: &str, der: &[u8]) -> String {
+    let body = base64::engine::general_purpose::STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Validates the server certificate with `inner`, optionally accepting a
+/// certificate issued for another host, then checks its key against the SPKI
+/// pins (if any).
+#[derive(Debug)]
+struct Verifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    pins: Vec<Vec<u8>>,
+    verify_hostname: bool,
+}
+
+impl Verifier {
+    fn is_pinned(&self, cert: &CertificateDer<'_>) -> bool {
+        self.pins.is_empty()
+            || spki_sha256(cert)
+                .is_some_and(|digest| self.pins.iter().any(|pin| pin[..] == digest[..]))
+    }
+}
+
+fn spki_sha256(cert: &CertificateDer<'_>) -> Option<Vec<u8>> {
+    let cert = webpki::EndEntityCert::try_from(cert).ok()?;
+    let spki = cert.subject_public_key_info();
+    Some(
+        aws_lc_rs::digest::digest(&aws_lc_rs::digest::SHA256, spki.as_ref())
+            .as_ref()
+            .to_vec(),
+    )
+}
+
+impl ServerCertVerifier for Verifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        let verified = match self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        ) {
+            // The host name is checked last, once the chain is known to be
+            // valid.
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) if !self.verify_hostname => ServerCertVerified::assertion(),
+            verified => verified?,
+        };
+        // Only the end entity: the intermediates are whatever the server
+        // chose to send, and unused ones are ignored by the validation.
+        if self.is_pinned(end_entity) {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(
+                "server certificate does not match any SPKI pin".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientBuilder;
+
+    const CERT: &str = "tests/data/tls/client.crt";
+    const KEY: &str = "tests/data/tls/client.key";
+
+    #[test]
+    fn client_identity_from_separate_files() {
+        let identity = Ssl::read_identity_pem(&CERT, &KEY).unwrap();
+        ClientBuilder::new("user", "localhost")
+            .ssl(Ssl {
+                client_identity: Some(identity),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn pem_bundle_keeps_every_certificate() {
+        let chain = "tests/data/tls/chain.crt";
+        assert_eq!(Ssl::read_pem_bundle(&chain).unwrap().len(), 2);
+        assert!(Ssl::read_pem(&chain).is_err());
+        Ssl::read_pem(&CERT).unwrap();
+    }
+
+    #[test]
+    fn client_identity_from_pkcs12() {
+        let p12 = "tests/data/tls/identity.p12";
+        let identity = Ssl::read_identity_pkcs12(&p12, "trino").unwrap();
+        // The leaf certificate, the CA that issued it, and the key.
+        assert_eq!(CertificateDer::pem_slice_iter(&identity.pem).count(), 2);
+        PrivateKeyDer::from_pem_slice(&identity.pem).unwrap();
+
+        let err = Ssl::read_identity_pkcs12(&p12, "wrong").err().unwrap();
+        assert!(matches!(err, crate::error::Error::Tls(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn client_identity_needs_a_key() {
+        let err = Ssl::read_identity_pem(&CERT, &CERT).err().unwrap();
+        assert!(matches!(err, crate::error::Error::Tls(_)), "got {err:?}");
+    }
+}
@@ -1,19 +1,30 @@
-#[derive(Debug, Copy, Clone)]
+use std::sync::{Arc, Mutex};
+
+use crate::client::{Client, ExecuteResult, RollbackOnDrop, RowStream, Statement};
+use crate::error::{Error, Result};
+use crate::query::Query;
+use crate::{DataSet, Trino};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransactionId {
     NoTransaction,
     StartTransaction,
     RollBack,
     Commit,
+    /// A transaction started on the coordinator, identified by the id returned
+    /// in `X-Trino-Started-Transaction-Id`.
+    Started(String),
 }
 
 impl TransactionId {
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(&self) -> &str {
         use TransactionId::*;
-        match *self {
+        match self {
             NoTransaction => "NONE",
             StartTransaction => "START TRANSACTION",
             RollBack => "ROLLBACK",
             Commit => "COMMIT",
+            Started(id) => id,
         }
     }
     pub fn from_str(s: &str) -> Option<Self> {
@@ -22,7 +33,8 @@ impl TransactionId {
             "START TRANSACTION" => Some(Self::StartTransaction),
             "ROLLBACK" => Some(Self::RollBack),
             "COMMIT" => Some(Self::Commit),
-            _ => None,
+            "" => None,
+            id => Some(Self::Started(id.to_string())),
         }
     }
 }
@@ -32,3 +44,125 @@ impl Default for TransactionId {
         TransactionId::NoTransaction
     }
 }
+
+pub(crate) type TransactionSlot = Arc<Mutex<TransactionId>>;
+
+/// An explicit, multi-statement Trino transaction, created by
+/// [`Client::begin_transaction`].
+///
+/// Statements run through the handle carry its transaction id; statements run
+/// directly on the [`Client`] (including from other tasks) stay outside it.
+/// Finish with [`commit`](Transaction::commit) or
+/// [`rollback`](Transaction::rollback).
+///
+/// # Rollback on drop
+/// Dropping an unfinished `Transaction` (an early return, an error, a dropped
+/// task) best-effort rolls it back on the coordinator with a fire-and-forget
+/// `ROLLBACK`. This requires a Tokio runtime to be active at drop time;
+/// otherwise the coordinator aborts the transaction once it has been idle for
+/// `transaction.idle-timeout`.
+///
+/// ```no_run
+/// # use trino_rust_client::client::ClientBuilder;
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new("user", "localhost").port(8080).build()?;
+/// let tx = client.begin_transaction().await?;
+/// tx.execute("INSERT INTO orders VALUES (1, 'open')").await?;
+/// tx.execute("DELETE FROM staging_orders WHERE id = 1").await?;
+/// tx.commit().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Transaction<'a> {
+    client: &'a Client,
+    slot: TransactionSlot,
+    rollback: Option<RollbackOnDrop>,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) async fn begin(client: &'a Client) -> Result<Self> {
+        let slot: TransactionSlot = Default::default();
+        let mut stmt = Statement::new("START TRANSACTION");
+        stmt.transaction = Some(slot.clone());
+        client.execute_statement(stmt).await?;
+
+        let id = match &*slot.lock().unwrap() {
+            TransactionId::Started(id) => id.clone(),
+            _ => {
+                return Err(Error::Protocol(
+                    "coordinator did not return a started transaction id".to_string(),
+                ))
+            }
+        };
+        Ok(Transaction {
+            client,
+            rollback: Some(client.rollback_on_drop(id).await),
+            slot,
+        })
+    }
+
+    /// The transaction id assigned by the coordinator.
+    pub fn id(&self) -> String {
+        self.slot.lock().unwrap().to_str().to_string()
+    }
+
+    /// Like [`Client::get_all`], inside this transaction.
+    pub async fn get_all<T>(&self, sql: impl Into<String>) -> Result<DataSet<T>>
+    where
+        T: Trino + 'static,
+        for<'de> T: serde::Deserialize<'de> + serde::Serialize,
+    {
+        self.client.get_all_statement(self.statement(sql)).await
+    }
+
+    /// Like [`Client::stream`], inside this transaction.
+    pub async fn stream<T>(&self, sql: impl Into<String>) -> Result<RowStream<'a, T>>
+    where
+        T: Trino + Send + 'static,
+        for<'de> T: serde::Deserialize<'de>,
+    {
+        self.client.stream_statement(self.statement(sql)).await
+    }
+
+    /// Like [`Client::execute`], inside this transaction.
+    pub async fn execute(&self, sql: impl Into<String>) -> Result<ExecuteResult> {
+        self.client.execute_statement(self.statement(sql)).await
+    }
+
+    /// Like [`Client::query`], inside this transaction.
+    pub fn query(&self, sql: impl Into<String>) -> Query<'a> {
+        self.client.query(sql).in_transaction(self.slot.clone())
+    }
+
+    /// Commit the transaction.
+    pub async fn commit(mut self) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    /// Roll the transaction back.
+    pub async fn rollback(mut self) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+
+    async fn finish(&mut self, sql: &str) -> Result<()> {
+        self.client.execute_statement(self.statement(sql)).await?;
+        // Only disarm on success: if the statement never reached the
+        // coordinator, dropping `self` still rolls the transaction back.
+        self.rollback = None;
+        Ok(())
+    }
+
+    fn statement(&self, sql: impl Into<String>) -> Statement {
+        let mut stmt = Statement::new(sql);
+        stmt.transaction = Some(self.slot.clone());
+        stmt
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if let Some(rollback) = self.rollback.take() {
+            rollback.spawn();
+        }
+    }
+}
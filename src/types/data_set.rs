@@ -2,12 +2,15 @@ use std::fmt;
 use std::marker::PhantomData;
 
 use iterable::Iterable;
-use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::ser::{SerializeStruct, Serializer};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use super::row::set_row_columns;
 use super::util::SerializeIterator;
-use super::{Context, Error, Trino, TrinoTy, VecSeed};
+use super::{Context, Error, Trino, TrinoTy};
+use crate::error::DecodeError;
 use crate::models::Column;
 use crate::Row;
 
@@ -19,7 +22,11 @@ pub struct DataSet<T: Trino> {
 
 impl<T: Trino> DataSet<T> {
     pub fn new(data: Vec<T>) -> Result<Self, Error> {
-        let types = match T::ty() {
+        let ty = match T::ty() {
+            TrinoTy::Lenient(ty) => *ty,
+            ty => ty,
+        };
+        let types = match ty {
             TrinoTy::Row(r) => {
                 if r.is_empty() {
                     return Err(Error::EmptyInTrinoRow);
@@ -27,6 +34,12 @@ impl<T: Trino> DataSet<T> {
                     r
                 }
             }
+            // the columns of tuple structs are named as Trino names unnamed columns
+            TrinoTy::Tuple(t) => t
+                .into_iter()
+                .enumerate()
+                .map(|(i, ty)| (format!("_col{}", i), ty))
+                .collect(),
             _ => return Err(Error::NoneTrinoRow),
         };
 
@@ -59,10 +72,11 @@ impl<T: Trino> DataSet<T> {
 }
 
 impl DataSet<Row> {
-    pub fn new_row(types: Vec<(String, TrinoTy)>, data: Vec<Row>) -> Result<Self, Error> {
+    pub fn new_row(types: Vec<(String, TrinoTy)>, mut data: Vec<Row>) -> Result<Self, Error> {
         if types.is_empty() {
             return Err(Error::EmptyInTrinoRow);
         }
+        set_row_columns(&mut data, &types);
         Ok(DataSet { types, data })
     }
 }
@@ -70,7 +84,7 @@ impl DataSet<Row> {
 /// Helper function to build DataSet from [`Vec<T>`]` without unsafe code
 /// Uses T::ty() to determine approach - only requires columns for Row type (Unknown)
 pub fn build_dataset<T: Trino + 'static>(
-    rows: Vec<T>,
+    mut rows: Vec<T>,
     columns: Option<Vec<Column>>,
 ) -> Result<DataSet<T>, crate::error::Error> {
     match T::ty() {
@@ -96,7 +110,9 @@ pub fn build_dataset<T: Trino + 'static>(
             }
 
             // Directly construct DataSet for Row type (same as deserializer does at line 241-242)
-            // We already have Vec<T> where T = Row, no need to deserialize
+            // We already have Vec<T> where T = Row, no need to deserialize, but the rows
+            // were decoded without the column types
+            set_row_columns(&mut rows, &types);
             Ok(DataSet { types, data: rows })
         }
         _ => {
@@ -161,70 +177,160 @@ impl<'de, T: Trino> Deserialize<'de> for DataSet<T> {
     where
         D: Deserializer<'de>,
     {
-        struct DataSetVisitor<T: Trino>(PhantomData<T>);
-
-        impl<'de, T: Trino> Visitor<'de> for DataSetVisitor<T> {
-            type Value = DataSet<T>;
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("struct DataSet")
-            }
+        let mut failure = None;
+        let visitor = DataSetVisitor {
+            failure: &mut failure,
+            _marker: PhantomData,
+        };
+        deserializer.deserialize_struct("DataSet", FIELDS, visitor)
+    }
+}
 
-            fn visit_map<V>(self, mut map: V) -> Result<DataSet<T>, V::Error>
-            where
-                V: MapAccess<'de>,
-            {
-                let types = if let Some(Field::Columns) = map.next_key()? {
-                    let columns: Vec<Column> = map.next_value()?;
-                    columns.try_map(TrinoTy::from_column).map_err(|e| {
-                        de::Error::custom(format!("deserialize trino type failed, reason: {}", e))
-                    })?
-                } else {
-                    return Err(de::Error::missing_field("columns"));
-                };
-
-                if types.is_empty() {
-                    // For empty columns (like PREPARE statements), skip data field processing
-                    // and just consume the data field if it exists
-                    if let Some(Field::Data) = map.next_key()? {
-                        let _: serde_json::Value = map.next_value()?; // consume and ignore
-                    }
-                    if let Some(Field::Columns) = map.next_key()? {
-                        return Err(de::Error::duplicate_field("columns"));
-                    }
-                    return Ok(DataSet {
-                        types,
-                        data: vec![],
-                    });
+impl<T: Trino> DataSet<T> {
+    /// Decode a result page, as `{"columns": [..], "data": [..]}`, with a
+    /// [`DecodeError`] locating a value that fails to decode.
+    #[cfg(feature = "spooling")]
+    pub(crate) fn decode_page(json: Value, page: Option<u64>) -> crate::error::Result<Self> {
+        let mut failure = None;
+        let visitor = DataSetVisitor {
+            failure: &mut failure,
+            _marker: PhantomData,
+        };
+        json.deserialize_struct("DataSet", FIELDS, visitor)
+            .map_err(|e| match failure {
+                Some(failure) => crate::error::Error::from(failure).at_page(page),
+                None => {
+                    crate::error::Error::Decode(format!("Failed to deserialize DataSet: {}", e))
                 }
+            })
+    }
+}
 
-                let array_ty = TrinoTy::Array(Box::new(TrinoTy::Row(types.clone())));
-                let ctx = Context::new::<Vec<T>>(&array_ty)
-                    .map_err(|e| de::Error::custom(format!("invalid trino type, reason: {}", e)))?;
-                let seed = VecSeed::new(&ctx);
+struct DataSetVisitor<'f, T: Trino> {
+    /// Set to where the data failed to decode.
+    failure: &'f mut Option<DecodeError>,
+    _marker: PhantomData<T>,
+}
 
-                let data = if let Some(Field::Data) = map.next_key()? {
-                    map.next_value_seed(seed)?
-                } else {
-                    // it is empty when there is no data
-                    vec![]
-                };
-
-                match map.next_key::<Field>()? {
-                    Some(Field::Columns) => return Err(de::Error::duplicate_field("columns")),
-                    Some(Field::Data) => return Err(de::Error::duplicate_field("data")),
-                    None => {}
-                }
+impl<'de, T: Trino> Visitor<'de> for DataSetVisitor<'_, T> {
+    type Value = DataSet<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct DataSet")
+    }
 
-                if let TrinoTy::Unknown = T::ty() {
-                    Ok(DataSet { types, data })
-                } else {
-                    DataSet::new(data).map_err(|e| {
-                        de::Error::custom(format!("construct data failed, reason: {}", e))
-                    })
-                }
+    fn visit_map<V>(self, mut map: V) -> Result<DataSet<T>, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let types = if let Some(Field::Columns) = map.next_key()? {
+            let columns: Vec<Column> = map.next_value()?;
+            columns.try_map(TrinoTy::from_column).map_err(|e| {
+                de::Error::custom(format!("deserialize trino type failed, reason: {}", e))
+            })?
+        } else {
+            return Err(de::Error::missing_field("columns"));
+        };
+
+        if types.is_empty() {
+            // For empty columns (like PREPARE statements), skip data field processing
+            // and just consume the data field if it exists
+            if let Some(Field::Data) = map.next_key()? {
+                let _: serde_json::Value = map.next_value()?; // consume and ignore
             }
+            if let Some(Field::Columns) = map.next_key()? {
+                return Err(de::Error::duplicate_field("columns"));
+            }
+            return Ok(DataSet {
+                types,
+                data: vec![],
+            });
+        }
+
+        let array_ty = TrinoTy::Array(Box::new(TrinoTy::Row(types.clone())));
+        let ctx = Context::new::<Vec<T>>(&array_ty)
+            .map_err(|e| de::Error::custom(format!("invalid trino type, reason: {}", e)))?;
+        let seed = RowsSeed::<T> {
+            ctx: &ctx,
+            _marker: PhantomData,
+        };
+
+        let data = if let Some(Field::Data) = map.next_key()? {
+            map.next_value_seed(seed)
+                .inspect_err(|_| *self.failure = ctx.take_error())?
+        } else {
+            // it is empty when there is no data
+            vec![]
+        };
+
+        match map.next_key::<Field>()? {
+            Some(Field::Columns) => return Err(de::Error::duplicate_field("columns")),
+            Some(Field::Data) => return Err(de::Error::duplicate_field("data")),
+            None => {}
         }
 
-        deserializer.deserialize_struct("DataSet", FIELDS, DataSetVisitor(PhantomData))
+        if let TrinoTy::Unknown = T::ty() {
+            Ok(DataSet { types, data })
+        } else {
+            DataSet::new(data)
+                .map_err(|e| de::Error::custom(format!("construct data failed, reason: {}", e)))
+        }
+    }
+}
+
+/// Decodes the rows of a result, recording the index of a row that fails.
+struct RowsSeed<'a, T> {
+    ctx: &'a Context<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'de, T: Trino> DeserializeSeed<'de> for RowsSeed<'_, T> {
+    type Value = Vec<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
     }
 }
+
+impl<'de, T: Trino> Visitor<'de> for RowsSeed<'_, T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("sequence of rows")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let TrinoTy::Array(row_ty) = self.ctx.ty() else {
+            panic!("invalid context")
+        };
+        let ctx = self.ctx.with_ty(row_ty);
+        let mut rows = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        loop {
+            match seq.next_element_seed(T::seed(&ctx)) {
+                Ok(Some(row)) => rows.push(row),
+                Ok(None) => return Ok(rows),
+                Err(e) => return Err(self.ctx.row_error(rows.len(), e)),
+            }
+        }
+    }
+}
+
+/// Decode `row`, a row of a result with `columns` that failed to decode into
+/// `T`, again by column to locate the value that fails.
+pub(crate) fn locate_row_error<T: Trino>(columns: &[Column], row: &Value) -> Option<DecodeError> {
+    let types = columns
+        .iter()
+        .cloned()
+        .map(TrinoTy::from_column)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let ty = TrinoTy::Row(types);
+    let ctx = Context::new::<T>(&ty).ok()?;
+    T::seed(&ctx).deserialize(row).err()?;
+    ctx.take_error()
+}
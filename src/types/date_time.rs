@@ -1,19 +1,19 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, Timelike};
 use std::fmt;
 
 use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
-use super::{Context, Trino, TrinoTy};
+use super::{Context, Trino, TrinoTy, TrinoZonedTimestamp};
 
 macro_rules! gen_date_time {
-    ($ty:ty, $seed:ident, $pty:expr, $format:expr, $empty:expr, $expect:expr) => {
+    ($ty:ty, $seed:ident, $pty:expr, $parse:expr, $value:expr, $empty:expr, $expect:expr) => {
         impl Trino for $ty {
             type ValueType<'a> = String;
             type Seed<'a, 'de> = $seed;
 
             fn value(&self) -> Self::ValueType<'_> {
-                self.format($format).to_string()
+                ($value)(self)
             }
 
             fn ty() -> TrinoTy {
@@ -42,7 +42,7 @@ macro_rules! gen_date_time {
             where
                 E: de::Error,
             {
-                <$ty>::parse_from_str(v, $format).map_err(|e| {
+                ($parse)(v).map_err(|e| {
                     de::Error::custom(format!("deserialize {} failed, reason: {}", $expect, e))
                 })
             }
@@ -60,19 +60,42 @@ macro_rules! gen_date_time {
     };
 }
 
+/// `%.3f`, `%.6f` or `%.9f`: the fewest fractional digits of the usual Trino
+/// precisions that represent `nanos` exactly.
+pub(super) fn fraction(nanos: u32) -> &'static str {
+    if nanos % 1_000_000 == 0 {
+        "%.3f"
+    } else if nanos % 1_000 == 0 {
+        "%.6f"
+    } else {
+        "%.9f"
+    }
+}
+
+// Values are parsed with any number of fractional digits, from `time(0)` /
+// `timestamp(0)` up to picosecond precision (`(12)`); digits beyond
+// nanoseconds are dropped, since chrono cannot represent them. The types are
+// declared with Trino's default precision of 3, but values keep any finer
+// digits they have.
+
 gen_date_time!(
     NaiveDate,
     NaiveDateSeed,
     TrinoTy::Date,
-    "%Y-%m-%d",
+    |v| NaiveDate::parse_from_str(v, "%Y-%m-%d"),
+    |v: &NaiveDate| v.format("%Y-%m-%d").to_string(),
     NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
     "naive date"
 );
 gen_date_time!(
     NaiveDateTime,
     NaiveDateTimeSeed,
-    TrinoTy::Timestamp,
-    "%Y-%m-%d %H:%M:%S%.3f",
+    TrinoTy::Timestamp(3),
+    |v| NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f"),
+    |v: &NaiveDateTime| {
+        let format = format!("%Y-%m-%d %H:%M:%S{}", fraction(v.nanosecond()));
+        v.format(&format).to_string()
+    },
     NaiveDate::from_ymd_opt(1970, 1, 1)
         .unwrap()
         .and_hms_opt(0, 0, 0)
@@ -82,16 +105,27 @@ gen_date_time!(
 gen_date_time!(
     NaiveTime,
     NaiveTimeSeed,
-    TrinoTy::Time,
-    "%H:%M:%S%.3f",
+    TrinoTy::Time(3),
+    |v| NaiveTime::parse_from_str(v, "%H:%M:%S%.f"),
+    |v: &NaiveTime| {
+        let format = format!("%H:%M:%S{}", fraction(v.nanosecond()));
+        v.format(&format).to_string()
+    },
     NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
     "naive date time"
 );
 gen_date_time!(
     DateTime<FixedOffset>,
     DateTimeWithZoneSeed,
-    TrinoTy::TimestampWithTimeZone,
-    "%Y-%m-%d %H:%M:%S%.3f %:z",
+    TrinoTy::TimestampWithTimeZone(3),
+    // Values in a named zone get the offset in effect there.
+    |v: &str| v.parse::<TrinoZonedTimestamp>().and_then(|ts| ts
+        .to_fixed_offset()
+        .ok_or_else(|| format!("{} does not exist in {}", ts.local, ts.zone))),
+    |v: &DateTime<FixedOffset>| {
+        let format = format!("%Y-%m-%d %H:%M:%S{} %:z", fraction(v.nanosecond()));
+        v.format(&format).to_string()
+    },
     DateTime::parse_from_str(
         "1970-01-01 00:00:00.000 +00:00",
         "%Y-%m-%d %H:%M:%S%.3f %:z"
@@ -1,6 +1,8 @@
+use std::fmt;
 use std::str::FromStr;
 
-use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use bigdecimal::{BigDecimal, Zero};
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
 use super::{Context, Error, Trino, TrinoTy};
 
@@ -30,7 +32,7 @@ impl<const P: usize, const S: usize> Trino for Decimal<P, S> {
     type Seed<'a, 'de> = DecimalSeed<P, S>;
 
     fn value(&self) -> Self::ValueType<'_> {
-        format!("{}", self.inner)
+        self.inner.to_plain_string()
     }
     fn ty() -> TrinoTy {
         TrinoTy::Decimal(P, S)
@@ -52,15 +54,85 @@ impl<'de, const P: usize, const S: usize> DeserializeSeed<'de> for DecimalSeed<P
     where
         D: Deserializer<'de>,
     {
-        let s = <&'de str as Deserialize<'de>>::deserialize(deserializer)?;
-        let d = bigdecimal::BigDecimal::from_str(s).map_err(de::Error::custom)?;
+        let d = deserializer.deserialize_any(DecimalVisitor)?;
+        let inner = fit(d, P, S).map_err(de::Error::custom)?;
+        Ok(Decimal { inner })
+    }
+}
+
+/// `d` with scale `s`, if that does not lose digits and leaves at most `p` of
+/// them.
+fn fit(d: BigDecimal, p: usize, s: usize) -> Result<BigDecimal, String> {
+    let scaled = d.with_scale(s as i64);
+    if scaled != d {
+        Err(format!("{} has more than {} fractional digits", d, s))
+    } else if !scaled.is_zero() && scaled.digits() > p as u64 {
+        Err(format!("{} does not fit in decimal({},{})", d, p, s))
+    } else {
+        Ok(scaled)
+    }
+}
+
+impl Trino for BigDecimal {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = BigDecimalSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        // `Display` switches to exponent notation for very small or large
+        // values, which `DECIMAL '...'` does not accept.
+        self.to_plain_string()
+    }
+    fn ty() -> TrinoTy {
+        TrinoTy::AnyDecimal
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        BigDecimalSeed
+    }
+
+    fn empty() -> Self {
+        Default::default()
+    }
+}
+
+pub struct BigDecimalSeed;
 
-        Ok(Decimal { inner: d })
+impl<'de> DeserializeSeed<'de> for BigDecimalSeed {
+    type Value = BigDecimal;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// Decodes a decimal, sent as a string, or an integer.
+struct DecimalVisitor;
+
+impl Visitor<'_> for DecimalVisitor {
+    type Value = BigDecimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<BigDecimal, E> {
+        BigDecimal::from_str(v).map_err(de::Error::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigDecimal, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigDecimal, E> {
+        Ok(v.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use super::*;
 
     #[test]
@@ -70,4 +142,27 @@ mod tests {
         let s = format!("{}", d.into_bigdecimal());
         assert_eq!(s, data);
     }
+
+    #[test]
+    fn narrowing_is_checked() {
+        let decode = |v| {
+            DecimalSeed::<5, 2>
+                .deserialize(&v)
+                .map(|d| d.inner.to_string())
+        };
+        assert_eq!(decode(json!("123.40")).unwrap(), "123.40");
+        assert_eq!(decode(json!("1.5000")).unwrap(), "1.50");
+        assert_eq!(decode(json!(12)).unwrap(), "12.00");
+        assert_eq!(
+            decode(json!("1.234")).unwrap_err().to_string(),
+            "1.234 has more than 2 fractional digits"
+        );
+        assert_eq!(
+            decode(json!(1000)).unwrap_err().to_string(),
+            "1000 does not fit in decimal(5,2)"
+        );
+
+        let d = BigDecimalSeed.deserialize(&json!("0.000001")).unwrap();
+        assert_eq!(d.to_string(), "0.000001");
+    }
 }
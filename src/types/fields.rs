@@ -0,0 +1,44 @@
+use serde::de::SeqAccess;
+use serde::ser::{SerializeTuple, Serializer};
+use serde::Serialize;
+
+use super::{Context, Trino, TrinoTy};
+
+/// The columns of a `#[derive(Trino)]` struct, implemented by the derive so
+/// that structs can be `#[trino(flatten)]`ed into one another.
+#[doc(hidden)]
+pub trait TrinoFields: Trino {
+    /// The number of columns, counting those of flattened fields.
+    const WIDTH: usize;
+
+    fn fields() -> Vec<(String, TrinoTy)>;
+
+    fn serialize_fields<S: SerializeTuple>(&self, tuple: &mut S) -> Result<(), S::Error>;
+
+    /// Decode the next value of `seq` into the column at `idx`.
+    fn access_seq<'a, 'de, A: SeqAccess<'de>>(
+        &mut self,
+        idx: usize,
+        seq: &mut A,
+        ctx: &'a Context<'a>,
+    ) -> Result<(), A::Error>;
+
+    /// The Rust type of the column at `idx`, for decode errors.
+    fn type_name(idx: usize) -> &'static str;
+}
+
+/// The value of a `#[derive(Trino)]` struct, serialized as a tuple of its
+/// columns.
+#[doc(hidden)]
+pub struct FieldsValue<'a, T>(pub &'a T);
+
+impl<T: TrinoFields> Serialize for FieldsValue<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(T::WIDTH)?;
+        self.0.serialize_fields(&mut tuple)?;
+        tuple.end()
+    }
+}
@@ -1,4 +1,8 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
 use super::{Context, Trino, TrinoFloat, TrinoMapKey, TrinoTy};
 
@@ -16,8 +20,10 @@ macro_rules! gen_float {
                 $pty
             }
 
-            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
-                $seed
+            fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed {
+                    exact: !matches!(ctx.ty(), TrinoTy::TrinoFloat(F32)),
+                }
             }
 
             fn empty() -> Self {
@@ -27,7 +33,12 @@ macro_rules! gen_float {
 
         impl TrinoMapKey for $ty {}
 
-        pub struct $seed;
+        pub struct $seed {
+            /// Whether a double must convert without rounding. Not for a
+            /// `real` column, whose values are sent as their shortest text,
+            /// which only round-trips through `f32`.
+            pub exact: bool,
+        }
 
         impl<'de> DeserializeSeed<'de> for $seed {
             type Value = $ty;
@@ -35,7 +46,23 @@ macro_rules! gen_float {
             where
                 D: Deserializer<'de>,
             {
-                Self::Value::deserialize(deserializer)
+                deserializer.deserialize_any(FloatVisitor::<$ty> {
+                    exact: self.exact,
+                    ty: PhantomData,
+                })
+            }
+        }
+
+        impl Float for $ty {
+            fn from_f64(v: f64, exact: bool) -> Option<Self> {
+                let f = v as $ty;
+                let kept = if exact { f as f64 == v } else { f.is_finite() };
+                (kept || !v.is_finite()).then_some(f)
+            }
+
+            fn from_i128(v: i128) -> Option<Self> {
+                let f = v as $ty;
+                (f as i128 == v).then_some(f)
             }
         }
     };
@@ -44,3 +71,104 @@ macro_rules! gen_float {
 use TrinoFloat::*;
 gen_float!(f32, F32Seed, TrinoTy::TrinoFloat(F32));
 gen_float!(f64, F64Seed, TrinoTy::TrinoFloat(F64));
+
+trait Float: Sized + FromStr {
+    /// `v`, unless it is out of range or, if `exact`, can not be
+    /// represented exactly.
+    fn from_f64(v: f64, exact: bool) -> Option<Self>;
+
+    /// `v`, unless it can not be represented exactly.
+    fn from_i128(v: i128) -> Option<Self>;
+}
+
+/// Decodes a float, an integer that converts to it without loss, or the
+/// strings Trino sends for `NaN` and infinities.
+struct FloatVisitor<T> {
+    exact: bool,
+    ty: PhantomData<T>,
+}
+
+impl<T: Float> FloatVisitor<T> {
+    fn convert<V: fmt::Display, E: de::Error>(v: V, f: Option<T>) -> Result<T, E> {
+        f.ok_or_else(|| {
+            E::custom(format!(
+                "{} can not be represented as {}",
+                v,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+impl<T: Float> Visitor<'_> for FloatVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a number fitting in {}",
+            std::any::type_name::<T>()
+        )
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<T, E> {
+        T::from_f64(v, self.exact).ok_or_else(|| {
+            E::custom(format!(
+                "double {} is out of range for {}",
+                v,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+        Self::convert(v, T::from_i128(v.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+        Self::convert(v, T::from_i128(v.into()))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeSeed;
+    use serde_json::json;
+
+    use super::*;
+
+    const F64: F64Seed = F64Seed { exact: true };
+    const F32: F32Seed = F32Seed { exact: true };
+    const REAL: F32Seed = F32Seed { exact: false };
+
+    #[test]
+    fn integers_and_special_values() {
+        assert_eq!(F64.deserialize(&json!(1.5)).unwrap(), 1.5);
+        assert_eq!(F64.deserialize(&json!(3)).unwrap(), 3.0);
+        assert!(F64.deserialize(&json!("NaN")).unwrap().is_nan());
+        assert_eq!(
+            F32.deserialize(&json!("-Infinity")).unwrap(),
+            f32::NEG_INFINITY
+        );
+
+        // 2^53 + 1 has no exact f64
+        assert!(F64.deserialize(&json!(9007199254740993_i64)).is_err());
+        assert!(F32.deserialize(&json!(1e300)).is_err());
+        assert!(REAL.deserialize(&json!(1e300)).is_err());
+        assert!(F32.deserialize(&json!("abc")).is_err());
+    }
+
+    #[test]
+    fn doubles_narrowed_exactly() {
+        assert_eq!(F32.deserialize(&json!(0.5)).unwrap(), 0.5);
+        let err = F32.deserialize(&json!(0.1)).unwrap_err();
+        assert_eq!(err.to_string(), "double 0.1 is out of range for f32");
+        // a real is sent as the shortest text of its f32
+        assert_eq!(REAL.deserialize(&json!(0.1)).unwrap(), 0.1_f32);
+    }
+}
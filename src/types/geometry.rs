@@ -0,0 +1,450 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::{Serialize, Serializer};
+use wkt::types::Dimension;
+use wkt::Wkt;
+
+use super::{Context, Trino, TrinoTy};
+
+/// A vertex of a [`TrinoGeometry`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrinoCoord {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A Trino `Geometry` or `SphericalGeography` value, decoded from the
+/// well-known text (WKT) the server sends, e.g. `POINT (1 2)` or
+/// `POLYGON ((0 0, 1 0, 1 1, 0 0))`.
+///
+/// Polygons are lists of rings, the exterior one first. Empty geometries
+/// (`POINT EMPTY`, `LINESTRING EMPTY`, ...) have no coordinates. Only 2D
+/// geometries are supported, as Trino does not return `Z` / `M` ones.
+///
+/// With the `geo` feature, it converts to and from `geo_types::Geometry`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrinoGeometry {
+    Point(Option<TrinoCoord>),
+    LineString(Vec<TrinoCoord>),
+    Polygon(Vec<Vec<TrinoCoord>>),
+    MultiPoint(Vec<TrinoCoord>),
+    MultiLineString(Vec<Vec<TrinoCoord>>),
+    MultiPolygon(Vec<Vec<Vec<TrinoCoord>>>),
+    GeometryCollection(Vec<TrinoGeometry>),
+}
+
+impl TrinoGeometry {
+    /// The WKT name of the geometry type, e.g. `POINT`.
+    pub fn kind(&self) -> &'static str {
+        use TrinoGeometry::*;
+        match self {
+            Point(_) => "POINT",
+            LineString(_) => "LINESTRING",
+            Polygon(_) => "POLYGON",
+            MultiPoint(_) => "MULTIPOINT",
+            MultiLineString(_) => "MULTILINESTRING",
+            MultiPolygon(_) => "MULTIPOLYGON",
+            GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        use TrinoGeometry::*;
+        match self {
+            Point(p) => p.is_none(),
+            LineString(c) | MultiPoint(c) => c.is_empty(),
+            Polygon(r) | MultiLineString(r) => r.is_empty(),
+            MultiPolygon(p) => p.is_empty(),
+            GeometryCollection(g) => g.is_empty(),
+        }
+    }
+}
+
+impl FromStr for TrinoGeometry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |e: &str| format!("invalid geometry {}: {}", s, e);
+        let wkt: Wkt<f64> = s.parse().map_err(|e: &str| invalid(e))?;
+        // The `wkt` crate stops reading at the end of the geometry.
+        let end = geometry_len(s);
+        if !s[end..].trim().is_empty() {
+            return Err(invalid(&format!("unexpected text at offset {}", end)));
+        }
+        from_wkt(wkt).map_err(|e| invalid(&e))
+    }
+}
+
+/// The length of the geometry at the start of `s`: up to the parenthesis
+/// closing its coordinates, or to its `EMPTY`.
+fn geometry_len(s: &str) -> usize {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => return i + 1,
+            ')' => depth -= 1,
+            'E' | 'e'
+                if depth == 0
+                    && s[i..]
+                        .get(..5)
+                        .is_some_and(|w| w.eq_ignore_ascii_case("EMPTY")) =>
+            {
+                return i + 5
+            }
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+/// Convert a geometry parsed by the `wkt` crate, which also accepts the `Z` /
+/// `M` coordinates and empty multipoint members `TrinoGeometry` cannot hold.
+fn from_wkt(wkt: Wkt<f64>) -> Result<TrinoGeometry, String> {
+    use TrinoGeometry::*;
+
+    if wkt.dimension() != Dimension::XY {
+        return Err(format!("unsupported {:?} coordinates", wkt.dimension()));
+    }
+    let geometry = match wkt {
+        Wkt::Point(p) => Point(p.coord().map(coord)),
+        Wkt::LineString(l) => LineString(coords(l)),
+        Wkt::Polygon(p) => Polygon(rings(p)),
+        Wkt::MultiPoint(m) => MultiPoint(
+            m.points()
+                .iter()
+                .map(|p| p.coord().map(coord))
+                .collect::<Option<_>>()
+                .ok_or("empty point in a MULTIPOINT")?,
+        ),
+        Wkt::MultiLineString(m) => {
+            MultiLineString(m.into_inner().0.into_iter().map(coords).collect())
+        }
+        Wkt::MultiPolygon(m) => MultiPolygon(m.into_inner().0.into_iter().map(rings).collect()),
+        Wkt::GeometryCollection(g) => GeometryCollection(
+            g.into_inner()
+                .0
+                .into_iter()
+                .map(from_wkt)
+                .collect::<Result<_, _>>()?,
+        ),
+    };
+    Ok(geometry)
+}
+
+fn coord(c: &wkt::types::Coord<f64>) -> TrinoCoord {
+    TrinoCoord { x: c.x, y: c.y }
+}
+
+fn coords(line: wkt::types::LineString<f64>) -> Vec<TrinoCoord> {
+    line.coords().iter().map(coord).collect()
+}
+
+fn rings(polygon: wkt::types::Polygon<f64>) -> Vec<Vec<TrinoCoord>> {
+    polygon.into_inner().0.into_iter().map(coords).collect()
+}
+
+impl fmt::Display for TrinoCoord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+/// Write `items` as a WKT list, or `EMPTY`.
+fn write_list<T>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[T],
+    mut item: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    if items.is_empty() {
+        return f.write_str("EMPTY");
+    }
+    f.write_str("(")?;
+    for (i, it) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        item(f, it)?;
+    }
+    f.write_str(")")
+}
+
+fn write_coords(f: &mut fmt::Formatter<'_>, coords: &[TrinoCoord]) -> fmt::Result {
+    write_list(f, coords, |f, c| write!(f, "{}", c))
+}
+
+fn write_rings(f: &mut fmt::Formatter<'_>, rings: &[Vec<TrinoCoord>]) -> fmt::Result {
+    write_list(f, rings, |f, c| write_coords(f, c))
+}
+
+impl fmt::Display for TrinoGeometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TrinoGeometry::*;
+
+        write!(f, "{} ", self.kind())?;
+        match self {
+            Point(p) => write_list(f, p.as_slice(), |f, c| write!(f, "{}", c)),
+            LineString(c) => write_coords(f, c),
+            Polygon(r) | MultiLineString(r) => write_rings(f, r),
+            MultiPoint(c) => write_list(f, c, |f, c| write!(f, "({})", c)),
+            MultiPolygon(p) => write_list(f, p, |f, r| write_rings(f, r)),
+            GeometryCollection(g) => write_list(f, g, |f, g| write!(f, "{}", g)),
+        }
+    }
+}
+
+impl Serialize for TrinoGeometry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TrinoGeometry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Trino for TrinoGeometry {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = TrinoGeometrySeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.to_string()
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::Geometry
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        TrinoGeometrySeed
+    }
+
+    fn empty() -> Self {
+        TrinoGeometry::Point(None)
+    }
+}
+
+pub struct TrinoGeometrySeed;
+
+impl<'de> DeserializeSeed<'de> for TrinoGeometrySeed {
+    type Value = TrinoGeometry;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        TrinoGeometry::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "geo")]
+mod geo {
+    use geo_types::{Coord, Geometry, LineString, Point, Polygon};
+
+    use super::{TrinoCoord, TrinoGeometry};
+
+    impl From<TrinoCoord> for Coord<f64> {
+        fn from(c: TrinoCoord) -> Self {
+            Coord { x: c.x, y: c.y }
+        }
+    }
+
+    impl From<Coord<f64>> for TrinoCoord {
+        fn from(c: Coord<f64>) -> Self {
+            TrinoCoord { x: c.x, y: c.y }
+        }
+    }
+
+    fn line_string(coords: Vec<TrinoCoord>) -> LineString<f64> {
+        coords.into_iter().map(Coord::from).collect()
+    }
+
+    fn polygon(mut rings: Vec<Vec<TrinoCoord>>) -> Polygon<f64> {
+        if rings.is_empty() {
+            return Polygon::new(LineString(vec![]), vec![]);
+        }
+        let exterior = line_string(rings.remove(0));
+        Polygon::new(exterior, rings.into_iter().map(line_string).collect())
+    }
+
+    fn coords(line: LineString<f64>) -> Vec<TrinoCoord> {
+        line.0.into_iter().map(TrinoCoord::from).collect()
+    }
+
+    fn rings(polygon: Polygon<f64>) -> Vec<Vec<TrinoCoord>> {
+        let (exterior, interiors) = polygon.into_inner();
+        if exterior.0.is_empty() {
+            return vec![];
+        }
+        std::iter::once(exterior)
+            .chain(interiors)
+            .map(coords)
+            .collect()
+    }
+
+    /// Fails on `POINT EMPTY`, which `geo_types` cannot represent.
+    impl TryFrom<TrinoGeometry> for Geometry<f64> {
+        type Error = String;
+
+        fn try_from(geometry: TrinoGeometry) -> Result<Self, Self::Error> {
+            let geometry = match geometry {
+                TrinoGeometry::Point(Some(c)) => Geometry::Point(Point(c.into())),
+                TrinoGeometry::Point(None) => {
+                    return Err("POINT EMPTY has no geo-types representation".to_string())
+                }
+                TrinoGeometry::LineString(c) => Geometry::LineString(line_string(c)),
+                TrinoGeometry::Polygon(r) => Geometry::Polygon(polygon(r)),
+                TrinoGeometry::MultiPoint(c) => {
+                    Geometry::MultiPoint(c.into_iter().map(|c| Point(c.into())).collect())
+                }
+                TrinoGeometry::MultiLineString(r) => {
+                    Geometry::MultiLineString(r.into_iter().map(line_string).collect())
+                }
+                TrinoGeometry::MultiPolygon(p) => {
+                    Geometry::MultiPolygon(p.into_iter().map(polygon).collect())
+                }
+                TrinoGeometry::GeometryCollection(g) => Geometry::GeometryCollection(
+                    g.into_iter()
+                        .map(Geometry::try_from)
+                        .collect::<Result<_, _>>()?,
+                ),
+            };
+            Ok(geometry)
+        }
+    }
+
+    /// `Line`, `Rect` and `Triangle` become a `LINESTRING` and `POLYGON`s.
+    impl From<Geometry<f64>> for TrinoGeometry {
+        fn from(geometry: Geometry<f64>) -> Self {
+            match geometry {
+                Geometry::Point(p) => TrinoGeometry::Point(Some(p.0.into())),
+                Geometry::Line(l) => TrinoGeometry::LineString(vec![l.start.into(), l.end.into()]),
+                Geometry::LineString(l) => TrinoGeometry::LineString(coords(l)),
+                Geometry::Polygon(p) => TrinoGeometry::Polygon(rings(p)),
+                Geometry::MultiPoint(m) => {
+                    TrinoGeometry::MultiPoint(m.0.into_iter().map(|p| p.0.into()).collect())
+                }
+                Geometry::MultiLineString(m) => {
+                    TrinoGeometry::MultiLineString(m.0.into_iter().map(coords).collect())
+                }
+                Geometry::MultiPolygon(m) => {
+                    TrinoGeometry::MultiPolygon(m.0.into_iter().map(rings).collect())
+                }
+                Geometry::GeometryCollection(g) => {
+                    TrinoGeometry::GeometryCollection(g.0.into_iter().map(Self::from).collect())
+                }
+                Geometry::Rect(r) => TrinoGeometry::Polygon(rings(r.to_polygon())),
+                Geometry::Triangle(t) => TrinoGeometry::Polygon(rings(t.to_polygon())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(wkt: &str) -> TrinoGeometry {
+        let geometry: TrinoGeometry = wkt.parse().unwrap();
+        assert_eq!(geometry.to_string(), wkt);
+        geometry
+    }
+
+    #[test]
+    fn parses_wkt() {
+        let c = |x, y| TrinoCoord { x, y };
+        assert_eq!(
+            round_trip("POINT (1 -2.5)"),
+            TrinoGeometry::Point(Some(c(1.0, -2.5)))
+        );
+        assert_eq!(round_trip("POINT EMPTY"), TrinoGeometry::Point(None));
+        assert_eq!(
+            round_trip("POLYGON ((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1))"),
+            TrinoGeometry::Polygon(vec![
+                vec![c(0.0, 0.0), c(4.0, 0.0), c(4.0, 4.0), c(0.0, 0.0)],
+                vec![c(1.0, 1.0), c(2.0, 1.0), c(2.0, 2.0), c(1.0, 1.0)],
+            ])
+        );
+        round_trip("MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))");
+        round_trip("GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1), LINESTRING EMPTY)");
+        round_trip("GEOMETRYCOLLECTION EMPTY");
+
+        // The older form without parentheses around each point.
+        let m: TrinoGeometry = "multipoint (1 2,3 4)".parse().unwrap();
+        assert_eq!(m, TrinoGeometry::MultiPoint(vec![c(1.0, 2.0), c(3.0, 4.0)]));
+        assert_eq!(m.to_string(), "MULTIPOINT ((1 2), (3 4))");
+    }
+
+    #[test]
+    fn rejects_invalid_wkt() {
+        for wkt in [
+            "",
+            "POINT",
+            "POINT (1)",
+            "POINT (1 2, 3 4)",
+            "POINT Z (1 2 3)",
+            "CIRCLE (1 2)",
+            "LINESTRING (0 0, 1 1",
+            "POINT (1 2) POINT (3 4)",
+            "POINT EMPTY (1 2)",
+            "LINESTRING EMPTY EMPTY",
+            // Unbalanced parentheses.
+            "POLYGON ((0 0, 1 0, 1 1, 0 0)",
+            "POLYGON (0 0, 1 0, 1 1, 0 0))",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0))",
+            "GEOMETRYCOLLECTION (POINT (1 2)",
+            "POINT (1 2))",
+            // Z / M coordinates, declared or not, also inside collections.
+            "POINT (1 2 3)",
+            "LINESTRING M (0 0 1, 1 1 2)",
+            "POLYGON ZM ((0 0 0 0, 1 0 0 0, 1 1 0 0, 0 0 0 0))",
+            "GEOMETRYCOLLECTION (POINT Z (1 2 3))",
+            "GEOMETRYCOLLECTION Z (POINT Z (1 2 3))",
+            // An empty point has no place in a multipoint.
+            "MULTIPOINT (EMPTY, (1 2))",
+            "MULTIPOINT (1 2, EMPTY)",
+        ] {
+            assert!(wkt.parse::<TrinoGeometry>().is_err(), "{}", wkt);
+        }
+    }
+
+    #[test]
+    fn parses_empty_members() {
+        let c = |x, y| TrinoCoord { x, y };
+        assert_eq!(
+            round_trip("GEOMETRYCOLLECTION (POINT EMPTY, POLYGON EMPTY, POINT (1 2))"),
+            TrinoGeometry::GeometryCollection(vec![
+                TrinoGeometry::Point(None),
+                TrinoGeometry::Polygon(vec![]),
+                TrinoGeometry::Point(Some(c(1.0, 2.0))),
+            ])
+        );
+        assert_eq!(
+            round_trip("MULTIPOLYGON (EMPTY, ((0 0, 1 0, 1 1, 0 0)))"),
+            TrinoGeometry::MultiPolygon(vec![
+                vec![],
+                vec![vec![c(0.0, 0.0), c(1.0, 0.0), c(1.0, 1.0), c(0.0, 0.0)]],
+            ])
+        );
+        round_trip("MULTILINESTRING (EMPTY, (0 0, 1 1))");
+        round_trip("GEOMETRYCOLLECTION (GEOMETRYCOLLECTION EMPTY)");
+    }
+
+    #[cfg(feature = "geo")]
+    #[test]
+    fn converts_to_and_from_geo_types() {
+        let wkt = "GEOMETRYCOLLECTION (POINT (1 2), POLYGON ((0 0, 1 0, 1 1, 0 0)))";
+        let geometry: TrinoGeometry = wkt.parse().unwrap();
+        let geo = geo_types::Geometry::try_from(geometry.clone()).unwrap();
+        assert_eq!(TrinoGeometry::from(geo), geometry);
+
+        assert!(geo_types::Geometry::try_from(TrinoGeometry::Point(None)).is_err());
+        let rect = geo_types::Rect::new((0.0, 0.0), (1.0, 1.0));
+        assert_eq!(
+            TrinoGeometry::from(geo_types::Geometry::Rect(rect)).kind(),
+            "POLYGON"
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
 use super::{Context, Trino, TrinoInt, TrinoMapKey, TrinoTy};
 
@@ -35,7 +38,7 @@ macro_rules! gen_int {
             where
                 D: Deserializer<'de>,
             {
-                Self::Value::deserialize(deserializer)
+                deserializer.deserialize_i64(IntVisitor::<$ty>(PhantomData))
             }
         }
     };
@@ -52,3 +55,67 @@ gen_int!(u8, U8Seed, TrinoTy::TrinoInt(I8));
 gen_int!(u16, U16Seed, TrinoTy::TrinoInt(I16));
 gen_int!(u32, U32Seed, TrinoTy::TrinoInt(I32));
 gen_int!(u64, U64Seed, TrinoTy::TrinoInt(I64));
+
+/// Decodes an integer of any width into `T`, failing on values out of its
+/// range instead of wrapping them.
+struct IntVisitor<T>(PhantomData<T>);
+
+impl<T> IntVisitor<T> {
+    fn convert<V, E>(v: V) -> Result<T, E>
+    where
+        T: TryFrom<V>,
+        V: fmt::Display + Copy,
+        E: de::Error,
+    {
+        T::try_from(v).map_err(|_| {
+            E::custom(format!(
+                "integer {} is out of range for {}",
+                v,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+impl<T> Visitor<'_> for IntVisitor<T>
+where
+    T: TryFrom<i64> + TryFrom<u64>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "an integer fitting in {}",
+            std::any::type_name::<T>()
+        )
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+        Self::convert(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+        Self::convert(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeSeed;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn narrowing_is_checked() {
+        assert_eq!(I8Seed.deserialize(&json!(-128)).unwrap(), -128);
+        assert_eq!(U8Seed.deserialize(&json!(255)).unwrap(), 255);
+        assert_eq!(I64Seed.deserialize(&json!(7)).unwrap(), 7);
+
+        let err = I8Seed.deserialize(&json!(300)).unwrap_err();
+        assert_eq!(err.to_string(), "integer 300 is out of range for i8");
+        assert!(U32Seed.deserialize(&json!(-1)).is_err());
+        assert!(I32Seed.deserialize(&json!(1.5)).is_err());
+    }
+}
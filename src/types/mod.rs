@@ -2,8 +2,10 @@ mod boolean;
 mod data_set;
 mod date_time;
 mod decimal;
+mod fields;
 mod fixed_char;
 mod float;
+mod geometry;
 mod integer;
 mod interval_day_to_second;
 mod interval_year_to_month;
@@ -13,9 +15,12 @@ mod map;
 mod option;
 mod row;
 mod seq;
+mod sketch;
 mod string;
+mod time_zone;
 mod util;
 pub mod uuid;
+mod value;
 mod var_binary;
 
 pub use self::uuid::*;
@@ -23,8 +28,10 @@ pub use boolean::*;
 pub use data_set::*;
 pub use date_time::*;
 pub use decimal::*;
+pub use fields::*;
 pub use fixed_char::*;
 pub use float::*;
+pub use geometry::*;
 pub use integer::*;
 pub use interval_day_to_second::*;
 pub use interval_year_to_month::*;
@@ -33,7 +40,10 @@ pub use map::*;
 pub use option::*;
 pub use row::*;
 pub use seq::*;
+pub use sketch::*;
 pub use string::*;
+pub use time_zone::*;
+pub use value::*;
 pub use var_binary::*;
 
 //mod str;
@@ -41,16 +51,18 @@ pub use var_binary::*;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
 use std::iter::FromIterator;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use crate::error::DecodeError;
 use crate::{
     ClientTypeSignatureParameter, Column, NamedTypeSignature, RawTrinoTy, RowFieldName,
     TypeSignature,
 };
 use derive_more::Display;
 use iterable::*;
-use serde::de::DeserializeSeed;
+use serde::de::{self, DeserializeSeed};
 use serde::Serialize;
 
 //TODO: refine it
@@ -86,10 +98,19 @@ pub trait Trino {
 
 pub trait TrinoMapKey: Trino {}
 
+/// The [`Context::row_map`] entry of a column that is not decoded.
+pub const IGNORED_COLUMN: usize = usize::MAX;
+
+/// The named fields of a row type, shared by the [`Row`]s decoded with it.
+pub type RowColumns = Arc<Vec<(String, TrinoTy)>>;
+
 #[derive(Debug)]
 pub struct Context<'a> {
     ty: &'a TrinoTy,
     map: Arc<HashMap<usize, Vec<usize>>>,
+    columns: Arc<HashMap<usize, RowColumns>>,
+    /// Where the value being decoded failed, filled in as the error unwinds.
+    failure: Arc<Mutex<Option<DecodeError>>>,
 }
 
 impl<'a> Context<'a> {
@@ -97,9 +118,15 @@ impl<'a> Context<'a> {
         let target = T::ty();
         let ret = extract(&target, provided)?;
         let map = HashMap::from_iter(ret);
+        let mut columns = HashMap::new();
+        if has_unknown(&target) {
+            collect_row_columns(provided, &mut columns);
+        }
         Ok(Context {
             ty: provided,
             map: Arc::new(map),
+            columns: Arc::new(columns),
+            failure: Default::default(),
         })
     }
 
@@ -107,6 +134,8 @@ impl<'a> Context<'a> {
         Context {
             ty,
             map: self.map.clone(),
+            columns: self.columns.clone(),
+            failure: self.failure.clone(),
         }
     }
 
@@ -114,10 +143,93 @@ impl<'a> Context<'a> {
         self.ty
     }
 
+    /// For the row being decoded, the index of the target field of each
+    /// column, or [`IGNORED_COLUMN`].
     pub fn row_map(&self) -> Option<&[usize]> {
         let key = self.ty as *const TrinoTy as usize;
         self.map.get(&key).map(|r| &**r)
     }
+
+    /// The fields of the row type being decoded into a dynamically typed
+    /// value, such as a [`Row`].
+    pub fn row_columns(&self) -> Option<RowColumns> {
+        let key = self.ty as *const TrinoTy as usize;
+        self.columns.get(&key).cloned()
+    }
+
+    /// Record that the column at `index`, named `name` and of the type of
+    /// this context, failed to decode into `rust_type` with `err`.
+    ///
+    /// A row nested in a column records its failing field first and is then
+    /// overwritten by the column, so the outermost column is kept, with the
+    /// path to the field in its message. Called by `#[derive(Trino)]` code.
+    #[doc(hidden)]
+    pub fn column_error<E: de::Error>(
+        &self,
+        index: usize,
+        name: &dyn fmt::Display,
+        rust_type: &'static str,
+        err: E,
+    ) -> E {
+        let mut failure = DecodeError::new(err.to_string());
+        failure.column = Some(name.to_string());
+        failure.column_index = Some(index);
+        failure.trino_type = Some(self.ty.full_type().into_owned());
+        failure.rust_type = Some(rust_type);
+        *self.failure.lock().unwrap_or_else(|e| e.into_inner()) = Some(failure);
+        E::custom(format_args!("column {}: {}", name, err))
+    }
+
+    /// Record that the row at `index` failed to decode with `err`, and name
+    /// its column in the returned error if it is known.
+    pub(crate) fn row_error<E: de::Error>(&self, index: usize, err: E) -> E {
+        let mut failure = self.failure.lock().unwrap_or_else(|e| e.into_inner());
+        let failure = failure.get_or_insert_with(|| DecodeError::new(err.to_string()));
+        failure.row = Some(index);
+        E::custom(&*failure)
+    }
+
+    /// The recorded failure, if a value failed to decode with this context.
+    pub(crate) fn take_error(&self) -> Option<DecodeError> {
+        self.failure
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+}
+
+/// Whether `ty` decodes some value dynamically.
+fn has_unknown(ty: &TrinoTy) -> bool {
+    use TrinoTy::*;
+
+    match ty {
+        Unknown => true,
+        Option(t) | Defaulted(t) | Lenient(t) | Array(t) => has_unknown(t),
+        Map(k, v) => has_unknown(k) || has_unknown(v),
+        Tuple(ts) => ts.iter().any(has_unknown),
+        Row(ts) => ts.iter().any(|(_, t)| has_unknown(t)),
+        _ => false,
+    }
+}
+
+/// Collect the fields of every row type in `provided`, by address.
+fn collect_row_columns(provided: &TrinoTy, columns: &mut HashMap<usize, RowColumns>) {
+    use TrinoTy::*;
+
+    match provided {
+        Row(ts) => {
+            let key = provided as *const TrinoTy as usize;
+            columns.insert(key, Arc::new(ts.clone()));
+            ts.iter().for_each(|(_, t)| collect_row_columns(t, columns));
+        }
+        Option(t) | Defaulted(t) | Array(t) | QDigest(t) => collect_row_columns(t, columns),
+        Map(k, v) => {
+            collect_row_columns(k, columns);
+            collect_row_columns(v, columns);
+        }
+        Tuple(ts) => ts.iter().for_each(|t| collect_row_columns(t, columns)),
+        _ => {}
+    }
 }
 
 fn extract(target: &TrinoTy, provided: &TrinoTy) -> Result<Vec<(usize, Vec<usize>)>, Error> {
@@ -125,19 +237,26 @@ fn extract(target: &TrinoTy, provided: &TrinoTy) -> Result<Vec<(usize, Vec<usize
 
     match (target, provided) {
         (Unknown, _) => Ok(vec![]),
-        (Decimal(p1, s1), Decimal(p2, s2)) if p1 == p2 && s1 == s2 => Ok(vec![]),
+        // Numbers decode into any type that can hold them; whether a value is
+        // narrowed without loss is checked as it is decoded.
+        (Decimal(_, _) | AnyDecimal, Decimal(_, _) | TrinoInt(_)) => Ok(vec![]),
         (Option(ty), provided) => extract(ty, provided),
+        (Defaulted(ty), provided) => extract(ty, provided),
         (Boolean, Boolean) => Ok(vec![]),
         (Date, Date) => Ok(vec![]),
-        (Time, Time) => Ok(vec![]),
-        (TimeWithTimeZone, TimeWithTimeZone) => Ok(vec![]),
-        (Timestamp, Timestamp) => Ok(vec![]),
-        (TimestampWithTimeZone, TimestampWithTimeZone) => Ok(vec![]),
+        // Any precision decodes into the same Rust type.
+        (Time(_), Time(_)) => Ok(vec![]),
+        (TimeWithTimeZone(_), TimeWithTimeZone(_)) => Ok(vec![]),
+        (Timestamp(_), Timestamp(_)) => Ok(vec![]),
+        (TimestampWithTimeZone(_), TimestampWithTimeZone(_)) => Ok(vec![]),
         (IntervalYearToMonth, IntervalYearToMonth) => Ok(vec![]),
         (IntervalDayToSecond, IntervalDayToSecond) => Ok(vec![]),
         (TrinoInt(_), TrinoInt(_)) => Ok(vec![]),
-        (TrinoFloat(_), TrinoFloat(_)) => Ok(vec![]),
-        (Varchar, Varchar) => Ok(vec![]),
+        (TrinoFloat(_), TrinoFloat(_) | TrinoInt(_)) => Ok(vec![]),
+        // Lengths are not checked, a `String` holds any of them.
+        (Varchar(_), Varchar(_) | Char(_)) => Ok(vec![]),
+        // Sent as `#rrggbb` or the name of a system color.
+        (Varchar(_), Color) => Ok(vec![]),
         (Char(a), Char(b)) if a == b => Ok(vec![]),
         (Tuple(t1), Tuple(t2)) => {
             if t1.len() != t2.len() {
@@ -146,67 +265,147 @@ fn extract(target: &TrinoTy, provided: &TrinoTy) -> Result<Vec<(usize, Vec<usize
                 t1.lazy_zip(t2).try_flat_map(|(l, r)| extract(l, r))
             }
         }
-        (Row(t1), Row(t2)) => {
+        // Tuple structs take the columns of a result by position.
+        (Tuple(t1), Row(t2)) => {
             if t1.len() != t2.len() {
                 Err(Error::InvalidTrinoType)
             } else {
-                // create a vector of the original element's reference
-                let t1k = t1.sorted_by(|t1, t2| Ord::cmp(&t1.0, &t2.0));
-                let t2k = t2.sorted_by(|t1, t2| Ord::cmp(&t1.0, &t2.0));
-
-                let ret = t1k.lazy_zip(t2k).try_flat_map(|(l, r)| {
-                    if l.0 == r.0 {
-                        extract(&l.1, &r.1)
-                    } else {
-                        Err(Error::InvalidTrinoType)
-                    }
-                })?;
-
-                let map = t2.map(|provided| t1.position(|target| provided.0 == target.0).unwrap());
-                let key = provided as *const TrinoTy as usize;
-                Ok(ret.add_one((key, map)))
+                t1.lazy_zip(t2).try_flat_map(|(l, (_, r))| extract(l, r))
             }
         }
+        (Row(t1), Row(t2)) => extract_row(t1, t2, provided, false),
+        (Lenient(t1), Row(t2)) => match &**t1 {
+            Row(t1) => extract_row(t1, t2, provided, true),
+            _ => Err(Error::InvalidTrinoType),
+        },
         (Array(t1), Array(t2)) => extract(t1, t2),
         (Map(t1k, t1v), Map(t2k, t2v)) => Ok(extract(t1k, t2k)?.chain(extract(t1v, t2v)?)),
         (IpAddress, IpAddress) => Ok(vec![]),
         (Uuid, Uuid) => Ok(vec![]),
         (Json, Json) => Ok(vec![]),
-        (VarBinary, VarBinary) => Ok(vec![]),
+        // Digests and MongoDB object ids are sent as varbinary payloads.
+        (VarBinary, VarBinary | TDigest | SetDigest | ObjectId) => Ok(vec![]),
+        // Both are sent as WKT, so either decodes into a `TrinoGeometry`.
+        (Geometry, Geometry | SphericalGeography) => Ok(vec![]),
+        (SphericalGeography, SphericalGeography) => Ok(vec![]),
+        // `P4HyperLogLog` is the same format, always dense.
+        (HyperLogLog, HyperLogLog | P4HyperLogLog) => Ok(vec![]),
+        (P4HyperLogLog, P4HyperLogLog) => Ok(vec![]),
+        (QDigest(t1), QDigest(t2)) => extract(t1, t2),
+        (TDigest, TDigest) => Ok(vec![]),
+        (SetDigest, SetDigest) => Ok(vec![]),
+        (Color, Color) => Ok(vec![]),
+        (ObjectId, ObjectId) => Ok(vec![]),
         _ => Err(Error::InvalidTrinoType),
     }
 }
 
-// Not yet decodable into a Rust type (these produce `Error::UnsupportedType`):
-// * TimeWithTimeZone
-// * HyperLogLog / P4HyperLogLog
-// * QDigest
-// * Geometry (Trino 482 added support alongside with Iceberg v3 tables)
+/// Match the fields of row type `t1` to the columns `t2` of `provided`, by
+/// name. A `lenient` row ignores extra columns and compares names
+/// case-insensitively.
+fn extract_row(
+    t1: &[(String, TrinoTy)],
+    t2: &[(String, TrinoTy)],
+    provided: &TrinoTy,
+    lenient: bool,
+) -> Result<Vec<(usize, Vec<usize>)>, Error> {
+    let names = |name: &str| {
+        if lenient {
+            name.to_lowercase()
+        } else {
+            name.to_owned()
+        }
+    };
+    let targets = t1.map(|(name, _)| names(name));
+
+    let mut found = vec![false; t1.len()];
+    let mut ret = vec![];
+    // for each provided column, the index of the target field it decodes into
+    let mut map = Vec::with_capacity(t2.len());
+    for (name, ty) in t2 {
+        let name = names(name);
+        let idx = match targets.iter().position(|target| *target == name) {
+            Some(idx) => idx,
+            None if lenient => {
+                map.push(IGNORED_COLUMN);
+                continue;
+            }
+            None => return Err(Error::InvalidTrinoType),
+        };
+        if found[idx] {
+            return Err(Error::InvalidTrinoType);
+        }
+        found[idx] = true;
+        ret.extend(extract(&t1[idx].1, ty)?);
+        map.push(idx);
+    }
+
+    // fields missing from the result must have a default
+    let missing = t1
+        .lazy_zip(found)
+        .any(|((_, ty), found)| !found && !matches!(ty, TrinoTy::Defaulted(_)));
+    if missing {
+        return Err(Error::InvalidTrinoType);
+    }
+
+    let key = provided as *const TrinoTy as usize;
+    Ok(ret.add_one((key, map)))
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TrinoTy {
     Date,
-    Time,
-    TimeWithTimeZone,
-    Timestamp,
-    TimestampWithTimeZone,
+    /// `time(p)`, with `p` fractional second digits (0 to 12).
+    Time(usize),
+    /// `time(p) with time zone`.
+    TimeWithTimeZone(usize),
+    /// `timestamp(p)`, with `p` fractional second digits (0 to 12).
+    Timestamp(usize),
+    /// `timestamp(p) with time zone`.
+    TimestampWithTimeZone(usize),
     Uuid,
     IntervalYearToMonth,
     IntervalDayToSecond,
     Option(Box<TrinoTy>),
+    /// A row field that may be missing from the result, like the
+    /// `#[trino(default)]` fields of a derived struct. Only found in the types
+    /// of Rust values, never in those of a result.
+    Defaulted(Box<TrinoTy>),
+    /// A row type that ignores the columns of the result it has no field for,
+    /// and matches field names case-insensitively, like `#[trino(lenient)]`
+    /// structs. Only found in the types of Rust values.
+    Lenient(Box<TrinoTy>),
     Boolean,
     TrinoInt(TrinoInt),
     TrinoFloat(TrinoFloat),
-    Varchar,
+    /// `varchar(n)`, or `None` for an unbounded `varchar`.
+    Varchar(Option<usize>),
     Char(usize),
     Tuple(Vec<TrinoTy>),
     Row(Vec<(String, TrinoTy)>),
     Array(Box<TrinoTy>),
     Map(Box<TrinoTy>, Box<TrinoTy>),
     Decimal(usize, usize),
+    /// A `decimal` of any precision and scale, the type of a `BigDecimal`.
+    /// Only found in the types of Rust values.
+    AnyDecimal,
     IpAddress,
     Json,
     VarBinary,
+    Geometry,
+    SphericalGeography,
+    HyperLogLog,
+    P4HyperLogLog,
+    /// `qdigest(T)`, digesting values of type `T`.
+    QDigest(Box<TrinoTy>),
+    TDigest,
+    SetDigest,
+    Color,
+    ObjectId,
     Unknown,
+    /// A type this client does not know, e.g. from a connector plugin, by
+    /// name. Its values only decode into `Row`, as raw JSON.
+    Other(String),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -230,10 +429,10 @@ impl TrinoTy {
 
         let ty = match sig.raw_type {
             RawTrinoTy::Date => TrinoTy::Date,
-            RawTrinoTy::Time => TrinoTy::Time,
-            RawTrinoTy::TimeWithTimeZone => TrinoTy::TimeWithTimeZone,
-            RawTrinoTy::Timestamp => TrinoTy::Timestamp,
-            RawTrinoTy::TimestampWithTimeZone => TrinoTy::TimestampWithTimeZone,
+            RawTrinoTy::Time => TrinoTy::Time(precision(sig)?),
+            RawTrinoTy::TimeWithTimeZone => TrinoTy::TimeWithTimeZone(precision(sig)?),
+            RawTrinoTy::Timestamp => TrinoTy::Timestamp(precision(sig)?),
+            RawTrinoTy::TimestampWithTimeZone => TrinoTy::TimestampWithTimeZone(precision(sig)?),
             RawTrinoTy::IntervalYearToMonth => TrinoTy::IntervalYearToMonth,
             RawTrinoTy::IntervalDayToSecond => TrinoTy::IntervalDayToSecond,
             RawTrinoTy::Unknown => TrinoTy::Unknown,
@@ -257,7 +456,7 @@ impl TrinoTy {
             RawTrinoTy::BigInt => TrinoTy::TrinoInt(I64),
             RawTrinoTy::Real => TrinoTy::TrinoFloat(F32),
             RawTrinoTy::Double => TrinoTy::TrinoFloat(F64),
-            RawTrinoTy::VarChar => TrinoTy::Varchar,
+            RawTrinoTy::VarChar => TrinoTy::Varchar(varchar_length(sig)?),
             RawTrinoTy::Char if sig.arguments.len() == 1 => {
                 if let ClientTypeSignatureParameter::LongLiteral(p) = sig.arguments.pop().unwrap() {
                     TrinoTy::Char(p as usize)
@@ -319,6 +518,24 @@ impl TrinoTy {
             RawTrinoTy::Uuid => TrinoTy::Uuid,
             RawTrinoTy::Json => TrinoTy::Json,
             RawTrinoTy::VarBinary => TrinoTy::VarBinary,
+            RawTrinoTy::Geometry => TrinoTy::Geometry,
+            RawTrinoTy::SphericalGeography => TrinoTy::SphericalGeography,
+            RawTrinoTy::HyperLogLog => TrinoTy::HyperLogLog,
+            RawTrinoTy::P4HyperLogLog => TrinoTy::P4HyperLogLog,
+            RawTrinoTy::TDigest => TrinoTy::TDigest,
+            RawTrinoTy::SetDigest => TrinoTy::SetDigest,
+            RawTrinoTy::Color => TrinoTy::Color,
+            RawTrinoTy::ObjectId => TrinoTy::ObjectId,
+            RawTrinoTy::Other(name) => TrinoTy::Other(name),
+            RawTrinoTy::QDigest if sig.arguments.len() == 1 => {
+                if let ClientTypeSignatureParameter::TypeSignature(sig) =
+                    sig.arguments.pop().unwrap()
+                {
+                    TrinoTy::QDigest(Box::new(Self::from_type_signature(sig)?))
+                } else {
+                    return Err(Error::InvalidTypeSignature);
+                }
+            }
             other => return Err(Error::UnsupportedType(other.to_str().to_string())),
         };
 
@@ -351,18 +568,21 @@ impl TrinoTy {
                 ClientTypeSignatureParameter::LongLiteral(p as u64),
                 ClientTypeSignatureParameter::LongLiteral(s as u64),
             ],
+            AnyDecimal => vec![],
             Date => vec![],
-            Time => vec![],
-            TimeWithTimeZone => vec![],
-            Timestamp => vec![],
-            TimestampWithTimeZone => vec![],
+            Time(p) | TimeWithTimeZone(p) | Timestamp(p) | TimestampWithTimeZone(p) => match p {
+                DEFAULT_PRECISION => vec![],
+                p => vec![ClientTypeSignatureParameter::LongLiteral(p as u64)],
+            },
             IntervalYearToMonth => vec![],
             IntervalDayToSecond => vec![],
-            Option(t) => return t.into_type_signature(),
+            Option(t) | Defaulted(t) | Lenient(t) => return t.into_type_signature(),
             Boolean => vec![],
             TrinoInt(_) => vec![],
             TrinoFloat(_) => vec![],
-            Varchar => vec![ClientTypeSignatureParameter::LongLiteral(2147483647)],
+            Varchar(n) => vec![ClientTypeSignatureParameter::LongLiteral(
+                n.map_or(UNBOUNDED_VARCHAR, |n| n as u64),
+            )],
             Char(a) => vec![ClientTypeSignatureParameter::LongLiteral(a as u64)],
             Tuple(ts) => ts.map(|ty| {
                 ClientTypeSignatureParameter::NamedTypeSignature(NamedTypeSignature {
@@ -387,6 +607,14 @@ impl TrinoTy {
             Uuid => vec![],
             Json => vec![],
             VarBinary => vec![],
+            Geometry => vec![],
+            SphericalGeography => vec![],
+            HyperLogLog => vec![],
+            P4HyperLogLog => vec![],
+            QDigest(t) => vec![ClientTypeSignatureParameter::TypeSignature(
+                t.into_type_signature(),
+            )],
+            TDigest | SetDigest | Color | ObjectId | Other(_) => vec![],
         };
 
         TypeSignature::new(raw_ty, params)
@@ -398,18 +626,26 @@ impl TrinoTy {
         match self {
             Unknown => RawTrinoTy::Unknown.to_str().into(),
             Decimal(p, s) => format!("{}({},{})", RawTrinoTy::Decimal.to_str(), p, s).into(),
-            Option(t) => t.full_type(),
+            AnyDecimal => RawTrinoTy::Decimal.to_str().into(),
+            Option(t) | Defaulted(t) | Lenient(t) => t.full_type(),
             Date => RawTrinoTy::Date.to_str().into(),
-            Time => RawTrinoTy::Time.to_str().into(),
-            TimeWithTimeZone => RawTrinoTy::TimeWithTimeZone.to_str().into(),
-            Timestamp => RawTrinoTy::Timestamp.to_str().into(),
-            TimestampWithTimeZone => RawTrinoTy::TimestampWithTimeZone.to_str().into(),
+            Time(DEFAULT_PRECISION) => RawTrinoTy::Time.to_str().into(),
+            TimeWithTimeZone(DEFAULT_PRECISION) => RawTrinoTy::TimeWithTimeZone.to_str().into(),
+            Timestamp(DEFAULT_PRECISION) => RawTrinoTy::Timestamp.to_str().into(),
+            TimestampWithTimeZone(DEFAULT_PRECISION) => {
+                RawTrinoTy::TimestampWithTimeZone.to_str().into()
+            }
+            Time(p) => format!("time({})", p).into(),
+            TimeWithTimeZone(p) => format!("time({}) with time zone", p).into(),
+            Timestamp(p) => format!("timestamp({})", p).into(),
+            TimestampWithTimeZone(p) => format!("timestamp({}) with time zone", p).into(),
             IntervalYearToMonth => RawTrinoTy::IntervalYearToMonth.to_str().into(),
             IntervalDayToSecond => RawTrinoTy::IntervalDayToSecond.to_str().into(),
             Boolean => RawTrinoTy::Boolean.to_str().into(),
-            TrinoInt(ty) => ty.raw_type().to_str().into(),
-            TrinoFloat(ty) => ty.raw_type().to_str().into(),
-            Varchar => RawTrinoTy::VarChar.to_str().into(),
+            TrinoInt(ty) => ty.raw_type().to_str().to_owned().into(),
+            TrinoFloat(ty) => ty.raw_type().to_str().to_owned().into(),
+            Varchar(None) => RawTrinoTy::VarChar.to_str().into(),
+            Varchar(Some(n)) => format!("{}({})", RawTrinoTy::VarChar.to_str(), n).into(),
             Char(a) => format!("{}({})", RawTrinoTy::Char.to_str(), a).into(),
             Tuple(ts) => format!(
                 "{}({})",
@@ -436,6 +672,16 @@ impl TrinoTy {
             Uuid => RawTrinoTy::Uuid.to_str().into(),
             Json => RawTrinoTy::Json.to_str().into(),
             VarBinary => RawTrinoTy::VarBinary.to_str().into(),
+            Geometry => RawTrinoTy::Geometry.to_str().into(),
+            SphericalGeography => RawTrinoTy::SphericalGeography.to_str().into(),
+            HyperLogLog => RawTrinoTy::HyperLogLog.to_str().into(),
+            P4HyperLogLog => RawTrinoTy::P4HyperLogLog.to_str().into(),
+            QDigest(t) => format!("{}({})", RawTrinoTy::QDigest.to_str(), t.full_type()).into(),
+            TDigest => RawTrinoTy::TDigest.to_str().into(),
+            SetDigest => RawTrinoTy::SetDigest.to_str().into(),
+            Color => RawTrinoTy::Color.to_str().into(),
+            ObjectId => RawTrinoTy::ObjectId.to_str().into(),
+            Other(name) => name.clone().into(),
         }
     }
 
@@ -445,18 +691,18 @@ impl TrinoTy {
         match self {
             Unknown => RawTrinoTy::Unknown,
             Date => RawTrinoTy::Date,
-            Time => RawTrinoTy::Time,
-            TimeWithTimeZone => RawTrinoTy::TimeWithTimeZone,
-            Timestamp => RawTrinoTy::Timestamp,
-            TimestampWithTimeZone => RawTrinoTy::TimestampWithTimeZone,
+            Time(_) => RawTrinoTy::Time,
+            TimeWithTimeZone(_) => RawTrinoTy::TimeWithTimeZone,
+            Timestamp(_) => RawTrinoTy::Timestamp,
+            TimestampWithTimeZone(_) => RawTrinoTy::TimestampWithTimeZone,
             IntervalYearToMonth => RawTrinoTy::IntervalYearToMonth,
             IntervalDayToSecond => RawTrinoTy::IntervalDayToSecond,
-            Decimal(_, _) => RawTrinoTy::Decimal,
-            Option(ty) => ty.raw_type(),
+            Decimal(_, _) | AnyDecimal => RawTrinoTy::Decimal,
+            Option(ty) | Defaulted(ty) | Lenient(ty) => ty.raw_type(),
             Boolean => RawTrinoTy::Boolean,
             TrinoInt(ty) => ty.raw_type(),
             TrinoFloat(ty) => ty.raw_type(),
-            Varchar => RawTrinoTy::VarChar,
+            Varchar(_) => RawTrinoTy::VarChar,
             Char(_) => RawTrinoTy::Char,
             Tuple(_) => RawTrinoTy::Row,
             Row(_) => RawTrinoTy::Row,
@@ -466,7 +712,49 @@ impl TrinoTy {
             Uuid => RawTrinoTy::Uuid,
             Json => RawTrinoTy::Json,
             VarBinary => RawTrinoTy::VarBinary,
+            Geometry => RawTrinoTy::Geometry,
+            SphericalGeography => RawTrinoTy::SphericalGeography,
+            HyperLogLog => RawTrinoTy::HyperLogLog,
+            P4HyperLogLog => RawTrinoTy::P4HyperLogLog,
+            QDigest(_) => RawTrinoTy::QDigest,
+            TDigest => RawTrinoTy::TDigest,
+            SetDigest => RawTrinoTy::SetDigest,
+            Color => RawTrinoTy::Color,
+            ObjectId => RawTrinoTy::ObjectId,
+            Other(name) => RawTrinoTy::Other(name.clone()),
+        }
+    }
+}
+
+/// The length Trino reports for an unbounded `varchar`.
+const UNBOUNDED_VARCHAR: u64 = 2147483647;
+
+/// The length argument of a `varchar` type signature; `None` when unbounded.
+fn varchar_length(mut sig: TypeSignature) -> Result<Option<usize>, Error> {
+    match sig.arguments.pop() {
+        None | Some(ClientTypeSignatureParameter::LongLiteral(UNBOUNDED_VARCHAR)) => Ok(None),
+        Some(ClientTypeSignatureParameter::LongLiteral(n)) if sig.arguments.is_empty() => {
+            Ok(Some(n as usize))
+        }
+        Some(_) => Err(Error::InvalidTypeSignature),
+    }
+}
+
+/// The precision of a `time` / `timestamp` type without an argument
+/// (milliseconds), which is also how Trino spells it.
+const DEFAULT_PRECISION: usize = 3;
+
+/// The precision argument of a `time` / `timestamp` type signature, 0 to 12
+/// (picoseconds). Servers that predate variable precision send none.
+fn precision(mut sig: TypeSignature) -> Result<usize, Error> {
+    match sig.arguments.pop() {
+        None => Ok(DEFAULT_PRECISION),
+        Some(ClientTypeSignatureParameter::LongLiteral(p))
+            if sig.arguments.is_empty() && p <= 12 =>
+        {
+            Ok(p as usize)
         }
+        Some(_) => Err(Error::InvalidTypeSignature),
     }
 }
 
@@ -1,18 +1,90 @@
+use std::any::Any;
+
 use serde::de::{Deserialize, DeserializeSeed, Deserializer};
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::{Context, Trino, TrinoTy};
+use crate::error::{DecodeError, Error, Result};
+use crate::{Context, RowColumns, Trino, TrinoTy, TrinoValue};
 
+/// A result row whose column types are only known at run time.
+///
+/// Values are kept as Trino sent them and decoded on access, against the
+/// column types of the result: `row.get::<NaiveDate>("day")` for a known Rust
+/// type, or `row.get::<TrinoValue>("day")` / [`values`](Row::values) for a
+/// [`TrinoValue`] matching the column type.
 #[derive(Debug, Clone, Serialize)]
 pub struct Row {
     data: Vec<Value>,
+    #[serde(skip)]
+    columns: Option<RowColumns>,
 }
 
 impl Row {
     pub fn into_json(self) -> Vec<Value> {
         self.data
     }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The column names and types, if the row was decoded as part of a result.
+    pub fn columns(&self) -> Option<&[(String, TrinoTy)]> {
+        self.columns.as_deref().map(|c| c.as_slice())
+    }
+
+    /// Decode the value of `column` into `T`.
+    pub fn get<T: Trino>(&self, column: &str) -> Result<T> {
+        let index = self
+            .columns()
+            .ok_or_else(|| Error::Decode("row has no column types".to_string()))?
+            .iter()
+            .position(|(name, _)| name == column)
+            .ok_or_else(|| Error::Decode(format!("no column {} in row", column)))?;
+        self.get_at(index)
+    }
+
+    /// Decode the value of the column at `index` into `T`.
+    pub fn get_at<T: Trino>(&self, index: usize) -> Result<T> {
+        let columns = self
+            .columns()
+            .ok_or_else(|| Error::Decode("row has no column types".to_string()))?;
+        let ((name, ty), value) = columns
+            .get(index)
+            .zip(self.data.get(index))
+            .ok_or_else(|| Error::Decode(format!("no column {} in row", index)))?;
+        let decode_err = |e: &dyn std::fmt::Display| {
+            let mut err = DecodeError::new(e.to_string());
+            err.column = Some(name.clone());
+            err.column_index = Some(index);
+            err.trino_type = Some(ty.full_type().into_owned());
+            err.rust_type = Some(std::any::type_name::<T>());
+            Error::from(err)
+        };
+        let ctx = Context::new::<T>(ty).map_err(|e| decode_err(&e))?;
+        T::seed(&ctx).deserialize(value).map_err(|e| decode_err(&e))
+    }
+
+    /// Every value, decoded according to its column type.
+    pub fn values(&self) -> Result<Vec<TrinoValue>> {
+        (0..self.len()).map(|i| self.get_at(i)).collect()
+    }
+}
+
+/// Give the rows in `rows`, if it is a `Vec<Row>`, the column types of their
+/// result.
+pub(crate) fn set_row_columns(rows: &mut dyn Any, columns: &[(String, TrinoTy)]) {
+    if let Some(rows) = rows.downcast_mut::<Vec<Row>>() {
+        let columns = RowColumns::new(columns.to_vec());
+        for row in rows {
+            row.columns = Some(columns.clone());
+        }
+    }
 }
 
 impl Trino for Row {
@@ -27,34 +99,47 @@ impl Trino for Row {
         TrinoTy::Unknown
     }
 
-    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
-        RowSeed
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        RowSeed {
+            columns: ctx.row_columns(),
+        }
     }
 
     fn empty() -> Self {
-        Row { data: vec![] }
+        Row {
+            data: vec![],
+            columns: None,
+        }
     }
 }
 
-pub struct RowSeed;
+pub struct RowSeed {
+    columns: Option<RowColumns>,
+}
 
 impl<'de> DeserializeSeed<'de> for RowSeed {
     type Value = Row;
-    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
     where
         D: Deserializer<'de>,
     {
         let data = <Vec<Value>>::deserialize(deserializer)?;
-        Ok(Row { data })
+        Ok(Row {
+            data,
+            columns: self.columns,
+        })
     }
 }
 
 impl<'de> Deserialize<'de> for Row {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let data = <Vec<Value>>::deserialize(deserializer)?;
-        Ok(Row { data })
+        Ok(Row {
+            data,
+            columns: None,
+        })
     }
 }
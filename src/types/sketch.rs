@@ -0,0 +1,482 @@
+use std::marker::PhantomData;
+
+use base64::Engine;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::{Serialize, Serializer};
+
+use super::{Context, Trino, TrinoTy};
+
+const SPARSE_V2: u8 = 2;
+const DENSE_V2: u8 = 3;
+
+/// Sparse entries keep this many leading hash bits, and the number of
+/// leading zeros after them in the remaining low bits.
+const PREFIX_BITS: u32 = 26;
+const VALUE_BITS: u32 = 6;
+const VALUE_MASK: u32 = (1 << VALUE_BITS) - 1;
+
+/// Dense registers store 4 bit deltas from a baseline; larger ones overflow.
+const MAX_DELTA: u8 = 15;
+
+/// A Trino `HyperLogLog` or `P4HyperLogLog` sketch, as produced by
+/// `approx_set` or `CAST(... AS HyperLogLog)`.
+///
+/// The serialized bytes are kept as received (`as_bytes`), so a sketch can be
+/// stored and sent back to Trino unchanged. Sketches from the same
+/// `approx_set` precision can be combined with [`merge`](Self::merge), and
+/// [`cardinality`](Self::cardinality) estimates the number of distinct values
+/// locally. The estimate uses the standard HyperLogLog formula without Trino's
+/// empirical bias correction tables, so it can differ slightly from
+/// `cardinality()` in Trino for mid-range counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperLogLog {
+    bytes: Vec<u8>,
+    index_bit_length: u8,
+    buckets: Buckets,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Buckets {
+    /// Sorted by prefix, one entry per prefix.
+    Sparse(Vec<u32>),
+    /// One register per bucket.
+    Dense(Vec<u8>),
+}
+
+impl HyperLogLog {
+    /// Parse a serialized sketch (the `SPARSE_V2` or `DENSE_V2` format).
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, String> {
+        let mut reader = Reader { bytes: &bytes };
+        let format = reader.u8()?;
+        let index_bit_length = reader.u8()?;
+        if !(1..=16).contains(&index_bit_length) {
+            return Err(format!(
+                "invalid HyperLogLog index bit length {}",
+                index_bit_length
+            ));
+        }
+        let buckets = match format {
+            SPARSE_V2 => {
+                let count = reader.u16()?;
+                let mut entries = (0..count)
+                    .map(|_| reader.u32())
+                    .collect::<Result<Vec<_>, _>>()?;
+                entries.sort_unstable_by_key(|e| e >> VALUE_BITS);
+                Buckets::Sparse(entries)
+            }
+            DENSE_V2 => {
+                let baseline = reader.u8()?;
+                let deltas = reader.take(number_of_buckets(index_bit_length) / 2)?;
+                let mut registers: Vec<u8> = deltas
+                    .iter()
+                    .flat_map(|d| {
+                        [
+                            baseline.saturating_add(d >> 4),
+                            baseline.saturating_add(d & 0x0f),
+                        ]
+                    })
+                    .collect();
+                let overflows = reader.u16()? as usize;
+                let buckets = (0..overflows)
+                    .map(|_| reader.u16())
+                    .collect::<Result<Vec<_>, _>>()?;
+                for bucket in buckets {
+                    let register = registers
+                        .get_mut(bucket as usize)
+                        .ok_or_else(|| format!("invalid HyperLogLog overflow bucket {}", bucket))?;
+                    *register = register.saturating_add(reader.u8()?);
+                }
+                Buckets::Dense(registers)
+            }
+            other => return Err(format!("unsupported HyperLogLog format {}", other)),
+        };
+        if !reader.bytes.is_empty() {
+            return Err("trailing bytes after HyperLogLog".to_string());
+        }
+        Ok(HyperLogLog {
+            bytes,
+            index_bit_length,
+            buckets,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// The number of bits of the hash used to select a bucket; the sketch has
+    /// `2^index_bit_length` buckets.
+    pub fn index_bit_length(&self) -> u8 {
+        self.index_bit_length
+    }
+
+    /// Estimate the number of distinct values added to the sketch.
+    pub fn cardinality(&self) -> u64 {
+        match &self.buckets {
+            Buckets::Sparse(entries) => {
+                let total = 1u64 << PREFIX_BITS;
+                linear_counting(total - entries.len() as u64, total)
+            }
+            Buckets::Dense(registers) => {
+                let m = registers.len() as u64;
+                let zeros = registers.iter().filter(|r| **r == 0).count() as u64;
+                if zeros as f64 > 0.4 * m as f64 {
+                    return linear_counting(zeros, m);
+                }
+                let sum: f64 = registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+                let alpha = match self.index_bit_length {
+                    4 => 0.673,
+                    5 => 0.697,
+                    6 => 0.709,
+                    _ => 0.7213 / (1.0 + 1.079 / m as f64),
+                };
+                (alpha * (m * m) as f64 / sum).round() as u64
+            }
+        }
+    }
+
+    /// Combine `other` into this sketch, which then estimates the number of
+    /// distinct values in the union of both. Fails if the sketches were built
+    /// with different index bit lengths.
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), String> {
+        if self.index_bit_length != other.index_bit_length {
+            return Err(format!(
+                "cannot merge HyperLogLog sketches with index bit lengths {} and {}",
+                self.index_bit_length, other.index_bit_length
+            ));
+        }
+        self.buckets = match (&self.buckets, &other.buckets) {
+            (Buckets::Sparse(a), Buckets::Sparse(b)) => {
+                let mut entries: Vec<u32> = a.iter().chain(b).copied().collect();
+                // Keep the largest value of each prefix.
+                entries
+                    .sort_unstable_by_key(|e| (e >> VALUE_BITS, std::cmp::Reverse(e & VALUE_MASK)));
+                entries.dedup_by_key(|e| *e >> VALUE_BITS);
+                let dense_size = number_of_buckets(self.index_bit_length) / 2;
+                if entries.len() * 4 > dense_size {
+                    Buckets::Dense(self.registers_of(&Buckets::Sparse(entries)))
+                } else {
+                    Buckets::Sparse(entries)
+                }
+            }
+            (a, b) => Buckets::Dense(
+                self.registers_of(a)
+                    .into_iter()
+                    .zip(self.registers_of(b))
+                    .map(|(a, b)| a.max(b))
+                    .collect(),
+            ),
+        };
+        self.bytes = self.to_bytes();
+        Ok(())
+    }
+
+    /// The dense registers equivalent to `buckets`.
+    fn registers_of(&self, buckets: &Buckets) -> Vec<u8> {
+        let entries = match buckets {
+            Buckets::Dense(registers) => return registers.clone(),
+            Buckets::Sparse(entries) => entries,
+        };
+        let p = self.index_bit_length as u32;
+        let rest_bits = PREFIX_BITS - p;
+        let mut registers = vec![0u8; number_of_buckets(self.index_bit_length)];
+        for entry in entries {
+            let prefix = entry >> VALUE_BITS;
+            let bucket = (prefix >> rest_bits) as usize;
+            let rest = prefix & ((1 << rest_bits) - 1);
+            // The leading zeros after the bucket bits, counting on into the
+            // hash bits summarized by the entry value when the rest is zero.
+            let zeros = if rest == 0 {
+                rest_bits + (entry & VALUE_MASK)
+            } else {
+                rest.leading_zeros() - (32 - rest_bits)
+            };
+            registers[bucket] = registers[bucket].max(zeros as u8 + 1);
+        }
+        registers
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match &self.buckets {
+            Buckets::Sparse(entries) => {
+                out.extend([SPARSE_V2, self.index_bit_length]);
+                out.extend((entries.len() as u16).to_le_bytes());
+                for entry in entries {
+                    out.extend(entry.to_le_bytes());
+                }
+            }
+            Buckets::Dense(registers) => {
+                let baseline = registers.iter().copied().min().unwrap_or(0);
+                let mut overflows = Vec::new();
+                let deltas = registers.iter().enumerate().map(|(bucket, r)| {
+                    let delta = r - baseline;
+                    if delta > MAX_DELTA {
+                        overflows.push((bucket as u16, delta - MAX_DELTA));
+                    }
+                    delta.min(MAX_DELTA)
+                });
+                let deltas: Vec<u8> = deltas
+                    .collect::<Vec<_>>()
+                    .chunks(2)
+                    .map(|d| d[0] << 4 | d[1])
+                    .collect();
+                out.extend([DENSE_V2, self.index_bit_length, baseline]);
+                out.extend(deltas);
+                out.extend((overflows.len() as u16).to_le_bytes());
+                for (bucket, _) in &overflows {
+                    out.extend(bucket.to_le_bytes());
+                }
+                out.extend(overflows.iter().map(|(_, value)| value));
+            }
+        }
+        out
+    }
+}
+
+fn number_of_buckets(index_bit_length: u8) -> usize {
+    1 << index_bit_length
+}
+
+fn linear_counting(zero_buckets: u64, total_buckets: u64) -> u64 {
+    let total = total_buckets as f64;
+    (total * (total / zero_buckets as f64).ln()).round() as u64
+}
+
+/// Reads the little endian fields of a serialized sketch.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < n {
+            return Err("truncated HyperLogLog".to_string());
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// A Trino `qdigest(T)` quantile digest, as produced by `qdigest_agg`. Only
+/// the serialized bytes are exposed; `T` is the type of the digested values
+/// (`i64`, `f64` or `f32`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QDigest<T> {
+    bytes: Vec<u8>,
+    _value: PhantomData<T>,
+}
+
+impl<T> QDigest<T> {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        QDigest {
+            bytes,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode<E: de::Error>(s: &str) -> Result<Vec<u8>, E> {
+    base64::engine::general_purpose::STANDARD
+        .decode(s.as_bytes())
+        .map_err(E::custom)
+}
+
+impl Serialize for HyperLogLog {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(&self.bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for HyperLogLog {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        HyperLogLog::from_bytes(decode(&s)?).map_err(de::Error::custom)
+    }
+}
+
+impl Trino for HyperLogLog {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = HyperLogLogSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        encode(&self.bytes)
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::HyperLogLog
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        HyperLogLogSeed
+    }
+
+    fn empty() -> Self {
+        HyperLogLog {
+            bytes: vec![SPARSE_V2, 11, 0, 0],
+            index_bit_length: 11,
+            buckets: Buckets::Sparse(vec![]),
+        }
+    }
+}
+
+pub struct HyperLogLogSeed;
+
+impl<'de> DeserializeSeed<'de> for HyperLogLogSeed {
+    type Value = HyperLogLog;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        HyperLogLog::deserialize(deserializer)
+    }
+}
+
+impl<T> Serialize for QDigest<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(&self.bytes))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for QDigest<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(QDigest::from_bytes(decode(&s)?))
+    }
+}
+
+impl<T: Trino> Trino for QDigest<T> {
+    type ValueType<'a>
+        = String
+    where
+        T: 'a;
+    type Seed<'a, 'de> = QDigestSeed<T>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        encode(&self.bytes)
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::QDigest(Box::new(T::ty()))
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        QDigestSeed(PhantomData)
+    }
+
+    fn empty() -> Self {
+        QDigest::from_bytes(Vec::new())
+    }
+}
+
+pub struct QDigestSeed<T>(PhantomData<T>);
+
+impl<'de, T> DeserializeSeed<'de> for QDigestSeed<T> {
+    type Value = QDigest<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        QDigest::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sparse sketch with index bit length 11 holding `entries`.
+    fn sparse(entries: &[u32]) -> HyperLogLog {
+        let mut bytes = vec![SPARSE_V2, 11];
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        for entry in entries {
+            bytes.extend(entry.to_le_bytes());
+        }
+        HyperLogLog::from_bytes(bytes).unwrap()
+    }
+
+    fn entry(prefix: u32, zeros: u32) -> u32 {
+        prefix << VALUE_BITS | zeros
+    }
+
+    #[test]
+    fn sparse_cardinality_and_merge() {
+        let mut a = sparse(&[entry(1, 0), entry(2 << 15, 3), entry(7 << 15, 1)]);
+        assert_eq!(a.cardinality(), 3);
+
+        let b = sparse(&[entry(2 << 15, 5), entry(9 << 15, 0)]);
+        a.merge(&b).unwrap();
+        assert_eq!(a.cardinality(), 4);
+        // The merged sketch is re-serialized, keeping the largest value.
+        let merged = HyperLogLog::from_bytes(a.as_bytes().to_vec()).unwrap();
+        assert_eq!(merged, a);
+        assert_eq!(
+            merged.buckets,
+            Buckets::Sparse(vec![
+                entry(1, 0),
+                entry(2 << 15, 5),
+                entry(7 << 15, 1),
+                entry(9 << 15, 0)
+            ])
+        );
+    }
+
+    #[test]
+    fn dense_round_trip_and_overflow() {
+        // The first 1000 of 2048 buckets hold a 1.
+        let entries: Vec<u32> = (0..1000).map(|i| entry(i << 15 | 1 << 14, 0)).collect();
+        let mut a = sparse(&entries[..500]);
+        a.merge(&sparse(&entries[500..])).unwrap();
+        assert!(matches!(a.buckets, Buckets::Dense(_)));
+        assert_eq!(a.as_bytes()[0], DENSE_V2);
+
+        // Push one register past the 4 bit delta into the overflow list.
+        let mut b = sparse(&[entry(3 << 15, 30)]);
+        b.merge(&a).unwrap();
+        let Buckets::Dense(registers) = &b.buckets else {
+            panic!("expected a dense sketch");
+        };
+        assert_eq!(registers[3], 15 + 30 + 1);
+        assert_eq!(HyperLogLog::from_bytes(b.as_bytes().to_vec()).unwrap(), b);
+
+        // Over 40% of the buckets are empty, so this is linear counting.
+        assert_eq!(b.cardinality(), linear_counting(1048, 2048));
+    }
+
+    #[test]
+    fn rejects_invalid_sketches() {
+        assert!(HyperLogLog::from_bytes(vec![]).is_err());
+        assert!(HyperLogLog::from_bytes(vec![1, 11]).is_err());
+        assert!(HyperLogLog::from_bytes(vec![SPARSE_V2, 11, 1, 0]).is_err());
+        assert!(HyperLogLog::from_bytes(vec![SPARSE_V2, 11, 0, 0, 0]).is_err());
+
+        let mut a = sparse(&[]);
+        let b = HyperLogLog::from_bytes(vec![SPARSE_V2, 12, 0, 0]).unwrap();
+        assert!(a.merge(&b).is_err());
+    }
+}
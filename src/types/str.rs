@@ -12,7 +12,7 @@ impl<'b> Trino for &'b str {
         *self
     }
     fn ty() -> TrinoTy {
-        TrinoTy::Varchar
+        TrinoTy::Varchar(None)
     }
 
     fn seed<'a, 'de>(_ty: &'a TrinoTy) -> Result<Self::Seed<'a, 'de>, Error> {
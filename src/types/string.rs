@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use serde::de::{Deserialize, DeserializeSeed, Deserializer};
 
 use super::{Context, Trino, TrinoMapKey, TrinoTy};
@@ -10,7 +12,7 @@ impl Trino for String {
         self
     }
     fn ty() -> TrinoTy {
-        TrinoTy::Varchar
+        TrinoTy::Varchar(None)
     }
     fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
         StringSeed
@@ -34,3 +36,36 @@ impl<'de> DeserializeSeed<'de> for StringSeed {
         Self::Value::deserialize(deserializer)
     }
 }
+
+impl Trino for Arc<str> {
+    type ValueType<'a> = &'a str;
+    type Seed<'a, 'de> = ArcStrSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self
+    }
+    fn ty() -> TrinoTy {
+        TrinoTy::Varchar(None)
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        ArcStrSeed
+    }
+
+    fn empty() -> Self {
+        "".into()
+    }
+}
+
+impl TrinoMapKey for Arc<str> {}
+
+pub struct ArcStrSeed;
+
+impl<'de> DeserializeSeed<'de> for ArcStrSeed {
+    type Value = Arc<str>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Into::into)
+    }
+}
@@ -0,0 +1,371 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, NaiveTime, Offset, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::{Serialize, Serializer};
+
+use super::date_time::fraction;
+use super::{Context, Trino, TrinoTy};
+
+/// The zone of a `timestamp with time zone` or `time with time zone` value as
+/// Trino reports it: a region id (`Europe/Paris`, `UTC`) or a fixed offset
+/// (`+02:00`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrinoTimeZone {
+    Named(Tz),
+    Offset(FixedOffset),
+}
+
+impl TrinoTimeZone {
+    /// The offset from UTC in this zone at the `local` date and time. For a
+    /// local time that occurs twice (at the end of daylight saving time) this
+    /// is the earlier one; `None` for one skipped over (at its start).
+    pub fn offset_at(&self, local: &NaiveDateTime) -> Option<FixedOffset> {
+        match self {
+            TrinoTimeZone::Named(tz) => tz
+                .offset_from_local_datetime(local)
+                .earliest()
+                .map(|offset| offset.fix()),
+            TrinoTimeZone::Offset(offset) => Some(*offset),
+        }
+    }
+}
+
+impl FromStr for TrinoTimeZone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with(['+', '-']) {
+            FixedOffset::from_str(s)
+                .map(TrinoTimeZone::Offset)
+                .map_err(|e| format!("invalid offset {}: {}", s, e))
+        } else {
+            Tz::from_str(s)
+                .map(TrinoTimeZone::Named)
+                .map_err(|e| format!("invalid time zone {}: {}", s, e))
+        }
+    }
+}
+
+impl fmt::Display for TrinoTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrinoTimeZone::Named(tz) => f.write_str(tz.name()),
+            TrinoTimeZone::Offset(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// A Trino `timestamp with time zone` value that keeps the zone it was
+/// reported in, region id included, where `DateTime<FixedOffset>` only keeps
+/// the offset.
+///
+/// `local` is the date and time as read on a clock in `zone`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrinoZonedTimestamp {
+    pub local: NaiveDateTime,
+    pub zone: TrinoTimeZone,
+}
+
+impl TrinoZonedTimestamp {
+    /// The instant, with the offset in effect in `zone`. `None` if `local`
+    /// does not exist in `zone`.
+    pub fn to_fixed_offset(&self) -> Option<DateTime<FixedOffset>> {
+        let offset = self.zone.offset_at(&self.local)?;
+        offset.from_local_datetime(&self.local).single()
+    }
+
+    /// The instant in `zone`, if it is a region id.
+    pub fn to_tz(&self) -> Option<DateTime<Tz>> {
+        match self.zone {
+            TrinoTimeZone::Named(tz) => tz.from_local_datetime(&self.local).earliest(),
+            TrinoTimeZone::Offset(_) => None,
+        }
+    }
+}
+
+impl FromStr for TrinoZonedTimestamp {
+    type Err = String;
+
+    /// `2024-01-01 10:00:00.000 Europe/Paris` or
+    /// `2024-01-01 10:00:00.000 +01:00`, with 0 to 12 fractional digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (local, zone) = s
+            .rsplit_once(' ')
+            .ok_or_else(|| format!("missing time zone in {}", s))?;
+        let local = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S%.f")
+            .map_err(|e| format!("invalid timestamp {}: {}", local, e))?;
+        Ok(TrinoZonedTimestamp {
+            local,
+            zone: zone.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for TrinoZonedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = format!("%Y-%m-%d %H:%M:%S{}", fraction(self.local.nanosecond()));
+        write!(f, "{} {}", self.local.format(&format), self.zone)
+    }
+}
+
+impl Serialize for TrinoZonedTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TrinoZonedTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Trino for TrinoZonedTimestamp {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = TrinoZonedTimestampSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.to_string()
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::TimestampWithTimeZone(3)
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        TrinoZonedTimestampSeed
+    }
+
+    fn empty() -> Self {
+        TrinoZonedTimestamp {
+            local: NaiveDateTime::default(),
+            zone: TrinoTimeZone::Named(Tz::UTC),
+        }
+    }
+}
+
+pub struct TrinoZonedTimestampSeed;
+
+impl<'de> DeserializeSeed<'de> for TrinoZonedTimestampSeed {
+    type Value = TrinoZonedTimestamp;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        TrinoZonedTimestamp::deserialize(deserializer)
+    }
+}
+
+/// A `timestamp with time zone` in a [`Tz`]. Values reported with an offset
+/// decode to `UTC` or, for whole hours, the matching `Etc/GMT` zone.
+impl Trino for DateTime<Tz> {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = DateTimeTzSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        TrinoZonedTimestamp {
+            local: self.naive_local(),
+            zone: TrinoTimeZone::Named(self.timezone()),
+        }
+        .to_string()
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::TimestampWithTimeZone(3)
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        DateTimeTzSeed
+    }
+
+    fn empty() -> Self {
+        Tz::UTC.timestamp_opt(0, 0).unwrap()
+    }
+}
+
+pub struct DateTimeTzSeed;
+
+impl<'de> DeserializeSeed<'de> for DateTimeTzSeed {
+    type Value = DateTime<Tz>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let ts = TrinoZonedTimestamp::deserialize(deserializer)?;
+        let tz = match ts.zone {
+            TrinoTimeZone::Named(tz) => tz,
+            TrinoTimeZone::Offset(offset) => offset_zone(offset).ok_or_else(|| {
+                de::Error::custom(format!("no time zone has the fixed offset {}", offset))
+            })?,
+        };
+        tz.from_local_datetime(&ts.local)
+            .earliest()
+            .ok_or_else(|| de::Error::custom(format!("{} does not exist in {}", ts.local, tz)))
+    }
+}
+
+/// A Trino `time with time zone` value: a time of day at a fixed offset from
+/// UTC, e.g. `01:02:03.456+08:00`.
+///
+/// Any precision from `time(0)` to `time(12)` decodes; digits beyond
+/// nanoseconds are dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeWithOffset {
+    pub time: NaiveTime,
+    pub offset: FixedOffset,
+}
+
+impl TimeWithOffset {
+    /// The same time of day in UTC.
+    pub fn to_utc(&self) -> NaiveTime {
+        self.time - chrono::Duration::seconds(self.offset.local_minus_utc().into())
+    }
+}
+
+impl FromStr for TimeWithOffset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let at = s
+            .rfind(['+', '-'])
+            .ok_or_else(|| format!("missing offset in {}", s))?;
+        let (time, offset) = s.split_at(at);
+        let time = NaiveTime::parse_from_str(time.trim_end(), "%H:%M:%S%.f")
+            .map_err(|e| format!("invalid time {}: {}", time, e))?;
+        let offset = FixedOffset::from_str(offset)
+            .map_err(|e| format!("invalid offset {}: {}", offset, e))?;
+        Ok(TimeWithOffset { time, offset })
+    }
+}
+
+impl fmt::Display for TimeWithOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = format!("%H:%M:%S{}", fraction(self.time.nanosecond()));
+        write!(f, "{}{}", self.time.format(&format), self.offset)
+    }
+}
+
+impl Serialize for TimeWithOffset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeWithOffset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Trino for TimeWithOffset {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = TimeWithOffsetSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.to_string()
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::TimeWithTimeZone(3)
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        TimeWithOffsetSeed
+    }
+
+    fn empty() -> Self {
+        TimeWithOffset {
+            time: NaiveTime::MIN,
+            offset: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+}
+
+pub struct TimeWithOffsetSeed;
+
+impl<'de> DeserializeSeed<'de> for TimeWithOffsetSeed {
+    type Value = TimeWithOffset;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        TimeWithOffset::deserialize(deserializer)
+    }
+}
+
+/// The `Etc/GMT` zone with `offset`. Their names have the POSIX sign, so
+/// `+02:00` is `Etc/GMT-2`.
+fn offset_zone(offset: FixedOffset) -> Option<Tz> {
+    let seconds = offset.local_minus_utc();
+    if seconds == 0 {
+        return Some(Tz::UTC);
+    }
+    if seconds % 3600 != 0 {
+        return None;
+    }
+    Tz::from_str(&format!("Etc/GMT{:+}", -seconds / 3600)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_and_offset_zones() {
+        let ts: TrinoZonedTimestamp = "2024-01-01 10:00:00.000 Europe/Paris".parse().unwrap();
+        assert_eq!(ts.zone, TrinoTimeZone::Named(Tz::Europe__Paris));
+        assert_eq!(
+            ts.to_fixed_offset().unwrap().to_rfc3339(),
+            "2024-01-01T10:00:00+01:00"
+        );
+        assert_eq!(ts.to_string(), "2024-01-01 10:00:00.000 Europe/Paris");
+
+        let ts: TrinoZonedTimestamp = "2024-01-01 10:00:00.123456 -05:30".parse().unwrap();
+        assert_eq!(
+            ts.zone,
+            TrinoTimeZone::Offset(FixedOffset::west_opt(5 * 3600 + 1800).unwrap())
+        );
+        assert!(ts.to_tz().is_none());
+        assert_eq!(ts.to_string(), "2024-01-01 10:00:00.123456 -05:30");
+
+        assert!("2024-01-01 10:00:00.000 Mars/Olympus"
+            .parse::<TrinoZonedTimestamp>()
+            .is_err());
+    }
+
+    #[test]
+    fn parses_time_with_offset() {
+        let t: TimeWithOffset = "01:02:03.456+08:00".parse().unwrap();
+        assert_eq!(t.time, NaiveTime::from_hms_milli_opt(1, 2, 3, 456).unwrap());
+        assert_eq!(t.offset, FixedOffset::east_opt(8 * 3600).unwrap());
+        assert_eq!(
+            t.to_utc(),
+            NaiveTime::from_hms_milli_opt(17, 2, 3, 456).unwrap()
+        );
+        assert_eq!(t.to_string(), "01:02:03.456+08:00");
+
+        let t: TimeWithOffset = "23:59:59.123456789012 -05:30".parse().unwrap();
+        assert_eq!(
+            t.time,
+            NaiveTime::from_hms_nano_opt(23, 59, 59, 123456789).unwrap()
+        );
+        assert_eq!(t.to_string(), "23:59:59.123456789-05:30");
+
+        let t: TimeWithOffset = "10:00:00+00:00".parse().unwrap();
+        assert_eq!(t.to_string(), "10:00:00.000+00:00");
+
+        assert!("10:00:00".parse::<TimeWithOffset>().is_err());
+    }
+
+    #[test]
+    fn offsets_map_to_etc_zones() {
+        assert_eq!(
+            offset_zone(FixedOffset::east_opt(2 * 3600).unwrap()),
+            Some(Tz::Etc__GMTMinus2)
+        );
+        assert_eq!(
+            offset_zone(FixedOffset::east_opt(0).unwrap()),
+            Some(Tz::UTC)
+        );
+        assert_eq!(offset_zone(FixedOffset::east_opt(1800).unwrap()), None);
+    }
+}
@@ -0,0 +1,361 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use base64::Engine;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use super::{
+    Context, HyperLogLog, IntervalDayToSecond, IntervalYearToMonth, TimeWithOffset, Trino,
+    TrinoGeometry, TrinoTy, TrinoZonedTimestamp,
+};
+
+/// A single Trino value, decoded according to its column type.
+///
+/// This is what [`Row`](crate::Row) values decode into when the Rust type is
+/// only known at run time, e.g. `row.get::<TrinoValue>("col")`. Decimals keep
+/// their exact value and bigints their full range, unlike
+/// `serde_json::Value`. Arrays, maps and rows hold `TrinoValue`s in turn;
+/// rows by position, their field names are in the column's [`TrinoTy`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrinoValue {
+    Null,
+    Boolean(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Decimal(BigDecimal),
+    Varchar(String),
+    Char(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    TimeWithTimeZone(TimeWithOffset),
+    Timestamp(NaiveDateTime),
+    TimestampWithTimeZone(TrinoZonedTimestamp),
+    IntervalYearToMonth(IntervalYearToMonth),
+    IntervalDayToSecond(IntervalDayToSecond),
+    Uuid(uuid::Uuid),
+    IpAddr(IpAddr),
+    /// `varbinary`, and the serialized digests and ids sent as such.
+    Bytes(Vec<u8>),
+    Json(Value),
+    Geometry(TrinoGeometry),
+    HyperLogLog(HyperLogLog),
+    Array(Vec<TrinoValue>),
+    /// Entries in the order Trino sent them.
+    Map(Vec<(TrinoValue, TrinoValue)>),
+    Row(Vec<TrinoValue>),
+    /// A value of a type this client does not know, as sent.
+    Unknown(Value),
+}
+
+impl TrinoValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, TrinoValue::Null)
+    }
+
+    /// Decode `value`, as Trino sent it, with the type `ctx.ty()`.
+    pub(super) fn decode(ctx: &Context, value: &Value) -> Result<Self, serde_json::Error> {
+        use TrinoTy::*;
+
+        fn seed<T: Trino>(ctx: &Context, value: &Value) -> Result<T, serde_json::Error> {
+            T::seed(ctx).deserialize(value)
+        }
+
+        if value.is_null() {
+            return Ok(TrinoValue::Null);
+        }
+        let v = match ctx.ty() {
+            Option(ty) | Defaulted(ty) | Lenient(ty) => {
+                return Self::decode(&ctx.with_ty(ty), value)
+            }
+            Boolean => TrinoValue::Boolean(seed(ctx, value)?),
+            TrinoInt(crate::TrinoInt::I8) => TrinoValue::I8(seed(ctx, value)?),
+            TrinoInt(crate::TrinoInt::I16) => TrinoValue::I16(seed(ctx, value)?),
+            TrinoInt(crate::TrinoInt::I32) => TrinoValue::I32(seed(ctx, value)?),
+            TrinoInt(crate::TrinoInt::I64) => TrinoValue::I64(seed(ctx, value)?),
+            // Trino sends `NaN` and infinities as strings.
+            TrinoFloat(crate::TrinoFloat::F32) => match value {
+                Value::String(s) => TrinoValue::F32(s.parse().map_err(de::Error::custom)?),
+                _ => TrinoValue::F32(seed(ctx, value)?),
+            },
+            TrinoFloat(crate::TrinoFloat::F64) => match value {
+                Value::String(s) => TrinoValue::F64(s.parse().map_err(de::Error::custom)?),
+                _ => TrinoValue::F64(seed(ctx, value)?),
+            },
+            Decimal(_, _) | AnyDecimal => {
+                let s = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                TrinoValue::Decimal(BigDecimal::from_str(&s).map_err(de::Error::custom)?)
+            }
+            Varchar(_) | Color => TrinoValue::Varchar(seed(ctx, value)?),
+            Char(_) => TrinoValue::Char(String::deserialize(value)?),
+            Date => TrinoValue::Date(seed(ctx, value)?),
+            Time(_) => TrinoValue::Time(seed(ctx, value)?),
+            TimeWithTimeZone(_) => TrinoValue::TimeWithTimeZone(seed(ctx, value)?),
+            Timestamp(_) => TrinoValue::Timestamp(seed(ctx, value)?),
+            TimestampWithTimeZone(_) => TrinoValue::TimestampWithTimeZone(seed(ctx, value)?),
+            IntervalYearToMonth => TrinoValue::IntervalYearToMonth(seed(ctx, value)?),
+            IntervalDayToSecond => TrinoValue::IntervalDayToSecond(seed(ctx, value)?),
+            Uuid => TrinoValue::Uuid(seed(ctx, value)?),
+            IpAddress => TrinoValue::IpAddr(seed(ctx, value)?),
+            VarBinary | QDigest(_) | TDigest | SetDigest | ObjectId => {
+                TrinoValue::Bytes(super::VarBinary::deserialize(value)?.0)
+            }
+            Json => TrinoValue::Json(value.clone()),
+            Geometry | SphericalGeography => TrinoValue::Geometry(seed(ctx, value)?),
+            HyperLogLog | P4HyperLogLog => TrinoValue::HyperLogLog(seed(ctx, value)?),
+            Array(ty) => {
+                let ctx = ctx.with_ty(ty);
+                let values = array(value)?;
+                TrinoValue::Array(
+                    values
+                        .iter()
+                        .map(|v| Self::decode(&ctx, v))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Map(key_ty, value_ty) => {
+                let key_ctx = ctx.with_ty(key_ty);
+                let value_ctx = ctx.with_ty(value_ty);
+                let entries = value
+                    .as_object()
+                    .ok_or_else(|| de::Error::custom(format!("expected a map, got {}", value)))?;
+                TrinoValue::Map(
+                    entries
+                        .iter()
+                        .map(|(k, v)| {
+                            let k = Self::decode(&key_ctx, &map_key(key_ty, k))?;
+                            Ok((k, Self::decode(&value_ctx, v)?))
+                        })
+                        .collect::<Result<_, serde_json::Error>>()?,
+                )
+            }
+            Row(fields) => {
+                let tys: Vec<&TrinoTy> = fields.iter().map(|(_, ty)| ty).collect();
+                TrinoValue::Row(Self::decode_fields(ctx, &tys, value)?)
+            }
+            Tuple(tys) => {
+                let tys: Vec<&TrinoTy> = tys.iter().collect();
+                TrinoValue::Row(Self::decode_fields(ctx, &tys, value)?)
+            }
+            Unknown | Other(_) => TrinoValue::Unknown(value.clone()),
+        };
+        Ok(v)
+    }
+
+    fn decode_fields(
+        ctx: &Context,
+        tys: &[&TrinoTy],
+        value: &Value,
+    ) -> Result<Vec<Self>, serde_json::Error> {
+        let values = array(value)?;
+        if values.len() != tys.len() {
+            return Err(de::Error::invalid_length(
+                values.len(),
+                &"one value per row field",
+            ));
+        }
+        tys.iter()
+            .zip(values)
+            .map(|(ty, v)| Self::decode(&ctx.with_ty(ty), v))
+            .collect()
+    }
+}
+
+fn array(value: &Value) -> Result<&[Value], serde_json::Error> {
+    value
+        .as_array()
+        .map(|values| values.as_slice())
+        .ok_or_else(|| de::Error::custom(format!("expected an array, got {}", value)))
+}
+
+/// Map keys arrive as JSON object keys, i.e. strings, even for numbers and
+/// booleans.
+fn map_key(ty: &TrinoTy, key: &str) -> Value {
+    match ty {
+        TrinoTy::TrinoInt(_) | TrinoTy::TrinoFloat(_) | TrinoTy::Boolean => {
+            serde_json::from_str(key).unwrap_or_else(|_| Value::String(key.to_string()))
+        }
+        _ => Value::String(key.to_string()),
+    }
+}
+
+/// Serializes as Trino sends the value.
+impl Serialize for TrinoValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use TrinoValue::*;
+
+        match self {
+            Null => serializer.serialize_none(),
+            Boolean(v) => v.serialize(serializer),
+            I8(v) => v.serialize(serializer),
+            I16(v) => v.serialize(serializer),
+            I32(v) => v.serialize(serializer),
+            I64(v) => v.serialize(serializer),
+            F32(v) => serialize_float(*v as f64, serializer),
+            F64(v) => serialize_float(*v, serializer),
+            Decimal(v) => v.to_plain_string().serialize(serializer),
+            Varchar(v) | Char(v) => v.serialize(serializer),
+            Date(v) => v.value().serialize(serializer),
+            Time(v) => v.value().serialize(serializer),
+            TimeWithTimeZone(v) => v.serialize(serializer),
+            Timestamp(v) => v.value().serialize(serializer),
+            TimestampWithTimeZone(v) => v.serialize(serializer),
+            IntervalYearToMonth(v) => v.value().serialize(serializer),
+            IntervalDayToSecond(v) => v.value().serialize(serializer),
+            Uuid(v) => v.value().serialize(serializer),
+            IpAddr(v) => v.serialize(serializer),
+            Bytes(v) => {
+                serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(v))
+            }
+            Json(v) | Unknown(v) => v.serialize(serializer),
+            Geometry(v) => v.serialize(serializer),
+            HyperLogLog(v) => v.serialize(serializer),
+            Array(values) | Row(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for v in values {
+                    seq.serialize_element(v)?;
+                }
+                seq.end()
+            }
+            Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Trino sends non-finite floats as strings.
+fn serialize_float<S: Serializer>(v: f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if v.is_nan() {
+        serializer.serialize_str("NaN")
+    } else if v.is_infinite() {
+        serializer.serialize_str(if v > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        v.serialize(serializer)
+    }
+}
+
+/// Decodes a value of any type, into the variant for the column's type.
+impl Trino for TrinoValue {
+    type ValueType<'a> = &'a TrinoValue;
+    type Seed<'a, 'de> = TrinoValueSeed<'a>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self
+    }
+
+    fn ty() -> TrinoTy {
+        TrinoTy::Unknown
+    }
+
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        TrinoValueSeed { ctx }
+    }
+
+    fn empty() -> Self {
+        TrinoValue::Null
+    }
+}
+
+pub struct TrinoValueSeed<'a> {
+    ctx: &'a Context<'a>,
+}
+
+impl<'de> DeserializeSeed<'de> for TrinoValueSeed<'_> {
+    type Value = TrinoValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        TrinoValue::decode(self.ctx, &value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TrinoFloat, TrinoInt};
+
+    fn decode(ty: TrinoTy, value: Value) -> TrinoValue {
+        let ctx = Context::new::<TrinoValue>(&ty).unwrap();
+        let decoded = TrinoValue::decode(&ctx, &value).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), value);
+        decoded
+    }
+
+    #[test]
+    fn decodes_by_column_type() {
+        assert_eq!(
+            decode(
+                TrinoTy::TrinoInt(TrinoInt::I64),
+                9007199254740993_i64.into()
+            ),
+            TrinoValue::I64(9007199254740993)
+        );
+        assert_eq!(
+            decode(TrinoTy::Decimal(38, 2), "12345678901234567890.12".into()),
+            TrinoValue::Decimal("12345678901234567890.12".parse().unwrap())
+        );
+        // serialized without exponent, however small or large
+        for d in [
+            "0.00000000000000000000000000000000000001",
+            "-10000000000000000000000000000000000000",
+        ] {
+            assert_eq!(
+                decode(TrinoTy::AnyDecimal, d.into()),
+                TrinoValue::Decimal(d.parse().unwrap())
+            );
+        }
+        assert_eq!(
+            decode(TrinoTy::Date, "2024-02-29".into()),
+            TrinoValue::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert!(matches!(
+            decode(TrinoTy::TrinoFloat(TrinoFloat::F64), "-Infinity".into()),
+            TrinoValue::F64(f) if f == f64::NEG_INFINITY
+        ));
+        assert_eq!(
+            decode(TrinoTy::Other("Bogus".into()), serde_json::json!({"a": 1})),
+            TrinoValue::Unknown(serde_json::json!({"a": 1}))
+        );
+    }
+
+    #[test]
+    fn decodes_nested_values() {
+        let ty = TrinoTy::Map(
+            Box::new(TrinoTy::TrinoInt(TrinoInt::I32)),
+            Box::new(TrinoTy::Array(Box::new(TrinoTy::Row(vec![
+                ("a".into(), TrinoTy::Boolean),
+                ("b".into(), TrinoTy::Varchar(None)),
+            ])))),
+        );
+        let value = serde_json::json!({ "1": [[true, "x"], null] });
+        assert_eq!(
+            decode(ty, value),
+            TrinoValue::Map(vec![(
+                TrinoValue::I32(1),
+                TrinoValue::Array(vec![
+                    TrinoValue::Row(vec![
+                        TrinoValue::Boolean(true),
+                        TrinoValue::Varchar("x".into())
+                    ]),
+                    TrinoValue::Null,
+                ])
+            )])
+        );
+    }
+}
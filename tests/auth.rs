@@ -0,0 +1,212 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use serde_json::json;
+use trino_rust_client::auth::{Auth, BoxError, TokenProvider};
+use trino_rust_client::client::{Client, ClientBuilder};
+use trino_rust_client::Row;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn make_mock_server() -> (MockServer, String, u16) {
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let host_port = uri.trim_start_matches("http://");
+    let (host, port_str) = host_port.rsplit_once(':').unwrap();
+    let port: u16 = port_str.parse().unwrap();
+    (server, host.to_string(), port)
+}
+
+#[tokio::test]
+async fn test_oauth2_external_authentication() {
+    let (server, host, port) = make_mock_server().await;
+    let uri = server.uri();
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(header("Authorization", "Bearer tok"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(401).insert_header(
+            "WWW-Authenticate",
+            format!(
+                r#"Bearer x_redirect_server="{uri}/oauth2/token/initiate/abc", x_token_server="{uri}/oauth2/token/abc""#
+            ),
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The token server makes the client wait once before handing out the token.
+    Mock::given(method("GET"))
+        .and(path("/oauth2/token/abc"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "nextUri": format!("{uri}/oauth2/token/abc/1") })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/oauth2/token/abc/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "tok" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let redirects = Arc::new(Mutex::new(vec![]));
+    let seen = redirects.clone();
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .auth(Auth::new_oauth2(move |url| {
+            seen.lock().unwrap().push(url.to_string())
+        }))
+        .auth_http_insecure(true)
+        .build()
+        .unwrap();
+
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+    // The cached token is reused without logging in again.
+    cli.get_all::<Row>("SELECT 2").await.unwrap();
+
+    assert_eq!(
+        *redirects.lock().unwrap(),
+        [format!("{uri}/oauth2/token/initiate/abc")]
+    );
+}
+
+#[tokio::test]
+async fn test_oauth2_token_server_error() {
+    let (server, host, port) = make_mock_server().await;
+    let uri = server.uri();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(401).insert_header(
+            "WWW-Authenticate",
+            format!(r#"Bearer x_token_server="{uri}/oauth2/token/abc""#),
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/oauth2/token/abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "error": "denied" })))
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .auth(Auth::new_oauth2(|_| {}))
+        .auth_http_insecure(true)
+        .build()
+        .unwrap();
+
+    let err = cli.get_all::<Row>("SELECT 1").await.unwrap_err();
+    assert!(
+        matches!(&err, trino_rust_client::error::Error::ExternalAuth(m) if m == "denied"),
+        "got {err:?}"
+    );
+}
+
+/// Hands out its tokens in order, then keeps returning the last one.
+struct Tokens(Mutex<Vec<&'static str>>);
+
+impl TokenProvider for Tokens {
+    fn token(&self) -> BoxFuture<'_, Result<String, BoxError>> {
+        let mut tokens = self.0.lock().unwrap();
+        let token = if tokens.len() > 1 {
+            tokens.remove(0)
+        } else {
+            tokens[0]
+        };
+        Box::pin(async move { Ok(token.to_string()) })
+    }
+}
+
+fn token_client(host: String, port: u16, tokens: Vec<&'static str>) -> Client {
+    ClientBuilder::new("test_user", host)
+        .port(port)
+        .auth(Auth::new_token_provider(Tokens(Mutex::new(tokens))))
+        .auth_http_insecure(true)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_token_provider_is_asked_again_on_401() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(header("Authorization", "Bearer fresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let cli = token_client(host, port, vec!["expired", "fresh"]);
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+    cli.get_all::<Row>("SELECT 2").await.unwrap();
+
+    let sent: Vec<_> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.headers["Authorization"].to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(sent, ["Bearer expired", "Bearer fresh", "Bearer fresh"]);
+}
+
+#[tokio::test]
+async fn test_token_provider_on_stream_cancel() {
+    let (server, host, port) = make_mock_server().await;
+    let uri = server.uri();
+    let finished: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string("tests/data/models/query_result_finished").unwrap(),
+    )
+    .unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "c", "infoUri": format!("{uri}/ui"),
+            "nextUri": format!("{uri}/v1/statement/c/2"),
+            "columns": finished["columns"], "data": finished["data"],
+            "stats": finished["stats"], "warnings": []
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/v1/query/c"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+
+    let cli = token_client(host, port, vec!["first", "rotated"]);
+    {
+        let mut stream = cli.stream::<Row>("SELECT 1").await.unwrap();
+        stream.next().await.unwrap().unwrap();
+    }
+
+    // The cancel runs on a spawned fire-and-forget task; poll the mock for it.
+    for _ in 0..40 {
+        let requests = server.received_requests().await.unwrap();
+        if let Some(cancel) = requests.iter().find(|r| r.url.path() == "/v1/query/c") {
+            assert_eq!(cancel.headers["Authorization"], "Bearer rotated");
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    panic!("dropping an unfinished RowStream should cancel the query");
+}
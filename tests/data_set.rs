@@ -11,9 +11,9 @@ use maplit::{btreemap, hashmap};
 use serde_json::value::Value;
 
 use std::net::IpAddr;
-use trino_rust_client::types::{DataSet, Decimal};
+use trino_rust_client::types::{build_dataset, DataSet, Decimal};
 use trino_rust_client::{Column, FixedChar, IntervalDayToSecond, IntervalYearToMonth, Row};
-use trino_rust_client::{Trino, TrinoFloat, TrinoInt, TrinoTy};
+use trino_rust_client::{Trino, TrinoFloat, TrinoInt, TrinoTy, TrinoValue};
 use uuid::Uuid;
 
 fn read(name: &str) -> (String, Value) {
@@ -408,6 +408,538 @@ fn test_date_time() {
     assert_eq!(ds[0], A { a, b, c, d });
 }
 
+#[test]
+fn test_row_get() {
+    let (s, v) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(ds.clone(), v.clone());
+    let row = &ds.as_slice()[0];
+
+    let a = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    assert_eq!(row.get::<NaiveDate>("a").unwrap(), a);
+    assert_eq!(row.get::<TrinoValue>("a").unwrap(), TrinoValue::Date(a));
+    assert_eq!(
+        row.get_at::<NaiveTime>(1).unwrap(),
+        NaiveTime::from_hms_milli_opt(1, 2, 3, 456).unwrap()
+    );
+    assert!(row.get::<NaiveDate>("missing").is_err());
+    assert!(row.get::<i64>("a").is_err());
+
+    let values = row.values().unwrap();
+    assert_eq!(values.len(), 4);
+    assert_eq!(values[0], TrinoValue::Date(a));
+    assert!(matches!(values[3], TrinoValue::TimestampWithTimeZone(_)));
+
+    // rows collected by the client are decoded without types and get them afterwards
+    let (columns, data) = split(v).unwrap();
+    let rows: Vec<Row> = serde_json::from_value(data).unwrap();
+    for err in [
+        rows[0].get::<NaiveDate>("a").unwrap_err(),
+        rows[0].get_at::<NaiveDate>(0).unwrap_err(),
+    ] {
+        assert!(
+            err.to_string().contains("row has no column types"),
+            "got {err}"
+        );
+    }
+    let ds = build_dataset(rows, Some(columns)).unwrap();
+    assert_eq!(ds.as_slice()[0].get::<NaiveDate>("a").unwrap(), a);
+}
+
+#[test]
+fn test_derive_attributes() {
+    mod raw {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+        use trino_rust_client::TrinoTy;
+
+        pub fn ty() -> TrinoTy {
+            TrinoTy::TimestampWithTimeZone(3)
+        }
+
+        pub fn serialize<S: Serializer>(v: &str, s: S) -> Result<S::Ok, S::Error> {
+            v.serialize(s)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+            String::deserialize(d)
+        }
+    }
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct Times {
+        b: NaiveTime,
+        c: NaiveDateTime,
+    }
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    #[trino(rename_all = "snake_case")]
+    struct A {
+        a: NaiveDate,
+        #[trino(flatten)]
+        times: Times,
+        #[trino(with = "raw")]
+        d: String,
+        #[trino(default)]
+        e: Option<i32>,
+        #[trino(skip)]
+        f: bool,
+    }
+
+    let (s, _) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    let a = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    let times = Times {
+        b: NaiveTime::from_hms_milli_opt(1, 2, 3, 456).unwrap(),
+        c: a.and_hms_milli_opt(3, 4, 5, 321).unwrap(),
+    };
+    let d = "2001-08-22 03:04:05.321 +00:00".to_string();
+    assert_eq!(
+        ds,
+        vec![A {
+            a,
+            times,
+            d,
+            e: None,
+            f: false
+        }]
+    );
+
+    // only fields with a default may be missing
+    #[derive(Trino, Debug)]
+    struct B {
+        a: NaiveDate,
+        b: NaiveTime,
+        c: NaiveDateTime,
+        d: DateTime<FixedOffset>,
+        e: Option<i32>,
+    }
+    assert!(serde_json::from_str::<DataSet<B>>(&s).is_err());
+}
+
+#[test]
+fn test_lenient() {
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    #[trino(lenient)]
+    struct A {
+        #[trino(rename = "A")]
+        a: NaiveDate,
+        d: DateTime<FixedOffset>,
+    }
+
+    let (s, _) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    let a = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    let d = DateTime::parse_from_rfc3339("2001-08-22T03:04:05.321Z").unwrap();
+    assert_eq!(ds, vec![A { a, d }]);
+
+    // without `lenient`, extra columns are an error
+    #[derive(Trino, Debug)]
+    struct B {
+        a: NaiveDate,
+        d: DateTime<FixedOffset>,
+    }
+    assert!(serde_json::from_str::<DataSet<B>>(&s).is_err());
+}
+
+#[test]
+fn test_tuple_struct_and_enum() {
+    #[derive(Trino, Eq, PartialEq, Debug, Clone, Copy)]
+    #[trino(rename_all = "lowercase")]
+    enum Letter {
+        A,
+        B,
+        #[trino(rename = "c")]
+        See,
+    }
+
+    #[derive(Trino, Eq, PartialEq, Debug, Clone)]
+    struct Pair(Letter, Option<String>);
+
+    let (s, _) = read("option");
+    let d = serde_json::from_str::<DataSet<Pair>>(&s).unwrap();
+    let (types, d) = d.split();
+    assert_eq!(types[0], ("_col0".to_string(), TrinoTy::Varchar(None)));
+    assert_eq!(
+        d,
+        vec![
+            Pair(Letter::A, None),
+            Pair(Letter::B, Some("Some(b)".to_string())),
+            Pair(Letter::See, None),
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(d[2].value()).unwrap(),
+        serde_json::json!(["c", null])
+    );
+
+    // unknown variants are an error
+    let s = s.replace(
+        "\"b\",\n            \"Some(b)\"",
+        "\"d\",\n            \"Some(b)\"",
+    );
+    assert!(serde_json::from_str::<DataSet<Pair>>(&s).is_err());
+
+    // named rows decode into tuple structs by position
+    #[derive(Trino, Eq, PartialEq, Debug, Clone)]
+    struct Point(i32, i32);
+
+    #[derive(Trino, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: Point,
+        b: i32,
+    }
+
+    let (s, _) = read("row");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    assert_eq!(
+        d,
+        vec![A {
+            a: Point(1, 1),
+            b: 5
+        }]
+    );
+
+    // tuple structs list their columns like named structs
+    use trino_rust_client::types::TrinoFields;
+    let int = TrinoTy::TrinoInt(TrinoInt::I32);
+    assert_eq!(
+        <Point as TrinoFields>::fields(),
+        vec![
+            ("_col0".to_string(), int.clone()),
+            ("_col1".to_string(), int)
+        ]
+    );
+
+    // variant names are renamed by character, not by byte
+    #[derive(Trino, Eq, PartialEq, Debug)]
+    #[trino(rename_all = "camelCase")]
+    enum Dessert {
+        Éclair,
+        CrèmeBrûlée,
+    }
+    assert_eq!(Dessert::Éclair.value(), "éclair");
+    assert_eq!(Dessert::CrèmeBrûlée.value(), "crèmeBrûlée");
+}
+
+#[test]
+fn test_coercion() {
+    use std::sync::Arc;
+
+    use bigdecimal::BigDecimal;
+
+    // a tinyint, b smallint, c integer, d bigint
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    #[trino(lenient)]
+    struct Ints {
+        a: i64,
+        b: f32,
+        c: BigDecimal,
+        d: Decimal<5, 1>,
+        f: i8,
+    }
+
+    let (s, _) = read("integer");
+    let d = serde_json::from_str::<DataSet<Ints>>(&s)
+        .unwrap()
+        .into_vec();
+    assert_eq!(d[0].a, -4);
+    assert_eq!(d[0].b, -3.0);
+    assert_eq!(d[0].c, BigDecimal::from(-2));
+    assert_eq!(d[0].d, "-1.0".parse().unwrap());
+    assert_eq!(d[0].f, 2);
+
+    // narrowing that loses data names the column
+    #[derive(Trino, Debug)]
+    #[trino(lenient)]
+    struct Unsigned {
+        d: u64,
+    }
+    let err = serde_json::from_str::<DataSet<Unsigned>>(&s).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("row 0, column d (#3, bigint as u64): integer -1 is out of range for u64"));
+
+    // a real, a double
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    #[trino(lenient)]
+    struct Floats {
+        a: f32,
+        b: f32,
+    }
+    let (_, mut v) = read("float");
+    v["data"] = serde_json::json!([[0.1, 0.5]]);
+    let d = serde_json::from_value::<DataSet<Floats>>(v.clone())
+        .unwrap()
+        .into_vec();
+    assert_eq!(d[0], Floats { a: 0.1, b: 0.5 });
+
+    v["data"] = serde_json::json!([[0.1, 0.1]]);
+    let err = serde_json::from_value::<DataSet<Floats>>(v).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("row 0, column b (#1, double as f32): double 0.1 is out of range for f32"));
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct Decimals {
+        a: BigDecimal,
+    }
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Decimals>>(&s)
+        .unwrap()
+        .into_vec();
+    assert_eq!(
+        d[0].a,
+        "1123412341234123412341234.2222222220"
+            .parse::<BigDecimal>()
+            .unwrap()
+    );
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct Chars {
+        a: Arc<str>,
+    }
+    let (s, _) = read("char");
+    let d = serde_json::from_str::<DataSet<Chars>>(&s)
+        .unwrap()
+        .into_vec();
+    assert_eq!(&*d[0].a, "abc");
+}
+
+#[test]
+fn test_decode_error() {
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: B,
+        b: i32,
+    }
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct B {
+        x: i32,
+        y: i32,
+    }
+
+    // the second row has a string for a.y
+    let (_, mut v) = read("row");
+    v["data"] = serde_json::json!([[[1, 1], 5], [[1, "y"], 6]]);
+    let err = serde_json::from_value::<DataSet<A>>(v)
+        .unwrap_err()
+        .to_string();
+    // the outermost column is named, with the path to the field that failed
+    assert!(
+        err.starts_with("row 1, column a (#0, row(x integer,y integer) as "),
+        "{}",
+        err
+    );
+    assert!(err.contains("::B): column y: "), "{}", err);
+
+    // a column of a `Row` decoded into the wrong type
+    let (s, _) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let err = match ds.as_slice()[0].get::<i64>("a") {
+        Err(trino_rust_client::error::Error::RowDecode(err)) => err,
+        other => panic!("expected a decode error, got {:?}", other),
+    };
+    assert_eq!(err.row, None);
+    assert_eq!(err.column.as_deref(), Some("a"));
+    assert_eq!(err.column_index, Some(0));
+    assert_eq!(err.trino_type.as_deref(), Some("date"));
+    assert_eq!(err.rust_type, Some("i64"));
+}
+
+#[test]
+fn test_date_time_precision() {
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: NaiveTime,
+        b: NaiveDateTime,
+        c: NaiveDateTime,
+        d: DateTime<FixedOffset>,
+    }
+
+    let (s, _) = read("date_time_precision");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let ds = ds.into_vec();
+
+    let date = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    let a = NaiveTime::from_hms_opt(1, 2, 3).unwrap();
+    let b = date.and_hms_micro_opt(3, 4, 5, 123456).unwrap();
+    let c = date.and_hms_nano_opt(3, 4, 5, 123456789).unwrap();
+    // Picoseconds are truncated to nanoseconds.
+    let d = DateTime::parse_from_rfc3339("2001-08-22T03:04:05.123456789+02:00").unwrap();
+    assert_eq!(ds[0], A { a, b, c, d });
+
+    // Values keep the digits they need, but at least milliseconds.
+    assert_eq!(a.value(), "01:02:03.000");
+    assert_eq!(b.value(), "2001-08-22 03:04:05.123456");
+    assert_eq!(c.value(), "2001-08-22 03:04:05.123456789");
+}
+
+#[test]
+fn test_timestamp_with_named_zone() {
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+    use trino_rust_client::{TrinoTimeZone, TrinoZonedTimestamp};
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: TrinoZonedTimestamp,
+        b: DateTime<Tz>,
+        c: DateTime<FixedOffset>,
+    }
+
+    // No round trip: `DateTime<FixedOffset>` keeps the offset, not the zone.
+    let (s, _) = read("timestamp_with_named_zone");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let ds = ds.into_vec();
+
+    let a = TrinoZonedTimestamp {
+        local: NaiveDate::from_ymd_opt(2024, 7, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+        zone: TrinoTimeZone::Named(Tz::Europe__Paris),
+    };
+    let b = Tz::America__New_York
+        .with_ymd_and_hms(2024, 1, 1, 10, 0, 0)
+        .unwrap();
+    let c = DateTime::parse_from_rfc3339("2024-01-01T10:00:00+05:30").unwrap();
+    assert_eq!(ds[0], A { a, b, c });
+    // Summer time in Paris.
+    assert_eq!(
+        a.to_fixed_offset().unwrap().to_rfc3339(),
+        "2024-07-01T10:00:00+02:00"
+    );
+}
+
+#[test]
+fn test_time_with_time_zone() {
+    use trino_rust_client::TimeWithOffset;
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: TimeWithOffset,
+        b: TimeWithOffset,
+    }
+
+    let (s, v) = read("time_with_time_zone");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let ds = ds.into_vec();
+    let a = TimeWithOffset {
+        time: NaiveTime::from_hms_milli_opt(1, 2, 3, 456).unwrap(),
+        offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+    };
+    let b = TimeWithOffset {
+        time: NaiveTime::from_hms_nano_opt(1, 2, 3, 123456789).unwrap(),
+        offset: FixedOffset::west_opt(5 * 3600 + 1800).unwrap(),
+    };
+    assert_eq!(ds[0], A { a, b });
+
+    // Untyped rows convert through serde.
+    let rows = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(rows.clone(), v);
+    let row = rows.into_vec().remove(0).into_json();
+    let b2: TimeWithOffset = serde_json::from_value(row[1].clone()).unwrap();
+    assert_eq!(b2, b);
+}
+
+#[test]
+fn test_geometry() {
+    use trino_rust_client::{TrinoCoord, TrinoGeometry};
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: TrinoGeometry,
+        b: TrinoGeometry,
+    }
+
+    let (s, v) = read("geometry");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    assert_eq!(d.len(), 2);
+    assert_eq!(d[0].a.kind(), "POLYGON");
+    assert_eq!(
+        d[0].b,
+        TrinoGeometry::Point(Some(TrinoCoord {
+            x: 2.3522,
+            y: 48.8566
+        }))
+    );
+    assert_eq!(d[1].a.to_string(), "MULTIPOINT ((1 2), (3 4))");
+    assert!(d[1].b.is_empty());
+
+    let rows = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(rows, v);
+}
+
+#[test]
+fn test_sketch() {
+    use trino_rust_client::{HyperLogLog, QDigest};
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: HyperLogLog,
+        b: HyperLogLog,
+        c: QDigest<i64>,
+    }
+
+    let (s, v) = read("sketch");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    assert_eq!(d.len(), 1);
+    let A { mut a, b, c } = d[0].clone();
+    assert_eq!(a.cardinality(), 2);
+    assert_eq!(b.index_bit_length(), 4);
+    assert_eq!(c.as_bytes(), (0..16).collect::<Vec<u8>>());
+
+    // Sketches of different precisions don't merge.
+    b.clone().merge(&a).unwrap_err();
+    a.merge(&a.clone()).unwrap();
+    assert_eq!(a.cardinality(), 2);
+
+    let rows = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(rows, v);
+}
+
+#[test]
+fn test_other_types() {
+    use trino_rust_client::VarBinary;
+
+    #[derive(Trino, PartialEq, Debug, Clone)]
+    struct A {
+        a: String,
+        b: String,
+        c: VarBinary,
+        d: VarBinary,
+        e: VarBinary,
+    }
+
+    let (s, v) = read("other");
+    let rows = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_eq!(
+        rows.clone().split().0,
+        vec![
+            ("a".to_string(), TrinoTy::Varchar(Some(10))),
+            ("b".to_string(), TrinoTy::Color),
+            ("c".to_string(), TrinoTy::TDigest),
+            ("d".to_string(), TrinoTy::SetDigest),
+            ("e".to_string(), TrinoTy::ObjectId),
+            ("f".to_string(), TrinoTy::Other("Bogus".to_string())),
+        ]
+    );
+    // Unknown types are kept as raw JSON.
+    let row = rows.clone().into_vec().remove(0).into_json();
+    assert_eq!(row[5], serde_json::json!({ "anything": [1, 2] }));
+    assert_ds(rows, v);
+
+    // Known types decode without the unknown column.
+    let mut v: Value = serde_json::from_str(&s).unwrap();
+    v["columns"].as_array_mut().unwrap().pop();
+    v["data"][0].as_array_mut().unwrap().pop();
+    let d = serde_json::from_value::<DataSet<A>>(v).unwrap().into_vec();
+    assert_eq!(d[0].a, "hello");
+    assert_eq!(d[0].b, "#ff0000");
+    assert_eq!(d[0].c, VarBinary(vec![1, 2, 3]));
+}
+
 #[test]
 fn test_decimal() {
     #[derive(Trino, PartialEq, Debug, Clone)]
@@ -479,7 +1011,7 @@ fn test_complex_row() {
     assert_eq!(
         t,
         vec![
-            ("a".into(), TrinoTy::Varchar),
+            ("a".into(), TrinoTy::Varchar(None)),
             ("b".into(), TrinoTy::TrinoInt(I32)),
             ("c".into(), TrinoTy::Boolean),
             ("d".into(), TrinoTy::Array(Box::new(TrinoTy::TrinoInt(I32)))),
@@ -130,16 +130,105 @@ fn test_name_attribute() {
         Renamed::ty(),
         TrinoTy::Row(vec![
             ("a".to_owned(), TrinoTy::TrinoInt(TrinoInt::I32)),
-            ("column with spaces".to_owned(), TrinoTy::Varchar)
+            ("column with spaces".to_owned(), TrinoTy::Varchar(None))
         ])
     );
     assert_eq!(result.value(), Tuple2(&42, &"...".to_owned()));
 }
 
+fn test_field_attributes() {
+    mod seconds {
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serializer};
+        use trino_rust_client::types::{TrinoInt, TrinoTy};
+
+        pub fn ty() -> TrinoTy {
+            TrinoTy::TrinoInt(TrinoInt::I64)
+        }
+
+        pub fn serialize<S: Serializer>(v: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_i64(v.timestamp())
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+            let secs = i64::deserialize(d)?;
+            DateTime::from_timestamp(secs, 0).ok_or_else(|| serde::de::Error::custom("out of range"))
+        }
+    }
+
+    #[derive(Trino)]
+    #[trino(rename_all = "camelCase")]
+    struct Audit {
+        created_by: String,
+        #[trino(default)]
+        updated_by: Option<String>,
+    }
+
+    #[derive(Trino)]
+    struct Event {
+        id: i64,
+        #[trino(flatten)]
+        audit: Audit,
+        #[trino(with = "seconds")]
+        at: chrono::DateTime<chrono::Utc>,
+        #[trino(skip)]
+        cached: Vec<u8>,
+    }
+
+    assert_eq!(
+        Event::ty(),
+        TrinoTy::Row(vec![
+            ("id".to_owned(), TrinoTy::TrinoInt(TrinoInt::I64)),
+            ("createdBy".to_owned(), TrinoTy::Varchar(None)),
+            (
+                "updatedBy".to_owned(),
+                TrinoTy::Defaulted(Box::new(TrinoTy::Option(Box::new(TrinoTy::Varchar(None)))))
+            ),
+            ("at".to_owned(), TrinoTy::TrinoInt(TrinoInt::I64)),
+        ])
+    );
+
+    let event = Event {
+        id: 1,
+        audit: Audit {
+            created_by: "a".to_owned(),
+            updated_by: None,
+        },
+        at: chrono::DateTime::from_timestamp(60, 0).unwrap(),
+        cached: vec![1],
+    };
+    assert_eq!(
+        serde_json::to_value(event.value()).unwrap(),
+        serde_json::json!([1, "a", null, 60])
+    );
+}
+
+fn test_tuple_struct_and_enum() {
+    #[derive(Trino)]
+    #[trino(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Status {
+        InProgress,
+        #[trino(rename = "done")]
+        Done,
+    }
+
+    #[derive(Trino)]
+    struct Job(i64, Status);
+
+    assert_eq!(
+        Job::ty(),
+        TrinoTy::Tuple(vec![TrinoTy::TrinoInt(TrinoInt::I64), TrinoTy::Varchar(None)])
+    );
+    assert_eq!(Job(1, Status::InProgress).value(), Tuple2(&1, "IN_PROGRESS"));
+    assert_eq!(Job(2, Status::Done).value(), Tuple2(&2, "done"));
+}
+
 fn main() {
     test_simple();
     test_nested();
     test_generic();
     test_wrap();
     test_name_attribute();
+    test_field_attributes();
+    test_tuple_struct_and_enum();
 }
@@ -0,0 +1,178 @@
+use std::fs;
+
+use trino_rust_client::client::ClientBuilder;
+use trino_rust_client::session::QueryOptions;
+use trino_rust_client::Row;
+use wiremock::matchers::{header as header_matcher, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+async fn make_mock_server() -> (MockServer, String, u16) {
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let host_port = uri.trim_start_matches("http://");
+    let (host, port_str) = host_port.rsplit_once(':').unwrap();
+    let port: u16 = port_str.parse().unwrap();
+    (server, host.to_string(), port)
+}
+
+fn header<'a>(req: &'a Request, name: &str) -> Vec<&'a str> {
+    req.headers
+        .get_all(name)
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_options_override_without_touching_shared_session() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    // Every response tries to switch the catalog, as `USE other.s` would.
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(finished)
+                .insert_header("X-Trino-Set-Catalog", "other"),
+        )
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .catalog("shared")
+        .property("a", "1")
+        .build()
+        .unwrap();
+
+    let opts = QueryOptions::new()
+        .catalog("tenant")
+        .property("b", "2")
+        .client_tag("dashboard");
+    cli.get_all_with::<Row>("SELECT 1", opts).await.unwrap();
+    cli.get_all::<Row>("SELECT 2").await.unwrap();
+    cli.get_all::<Row>("SELECT 3").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(header(&requests[0], "X-Trino-Catalog"), ["tenant"]);
+    let mut session = header(&requests[0], "X-Trino-Session");
+    session.sort();
+    assert_eq!(session, ["a=1", "b=2"]);
+    assert_eq!(header(&requests[0], "X-Trino-Client-Tags"), ["dashboard"]);
+
+    // The options neither leaked into, nor were overwritten by, the shared
+    // session...
+    assert_eq!(header(&requests[1], "X-Trino-Catalog"), ["shared"]);
+    assert_eq!(header(&requests[1], "X-Trino-Session"), ["a=1"]);
+    // ...while a plain call still applies the coordinator's session changes.
+    assert_eq!(header(&requests[2], "X-Trino-Catalog"), ["other"]);
+}
+
+#[tokio::test]
+async fn test_fork_has_its_own_session() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    // Only queries on the fork switch the schema.
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(header_matcher("X-Trino-Catalog", "tenant"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(finished.clone())
+                .insert_header("X-Trino-Set-Schema", "switched"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .catalog("shared")
+        .schema("base")
+        .build()
+        .unwrap();
+    let fork = cli.fork_with(QueryOptions::new().catalog("tenant")).await;
+
+    fork.get_all::<Row>("SELECT 1").await.unwrap();
+    fork.get_all::<Row>("SELECT 2").await.unwrap();
+    cli.get_all::<Row>("SELECT 3").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(header(&requests[0], "X-Trino-Catalog"), ["tenant"]);
+    assert_eq!(header(&requests[0], "X-Trino-Schema"), ["base"]);
+    // The fork keeps the coordinator's session changes for itself...
+    assert_eq!(header(&requests[1], "X-Trino-Catalog"), ["tenant"]);
+    assert_eq!(header(&requests[1], "X-Trino-Schema"), ["switched"]);
+    // ...and the parent never sees them, nor the fork's options.
+    assert_eq!(header(&requests[2], "X-Trino-Catalog"), ["shared"]);
+    assert_eq!(header(&requests[2], "X-Trino-Schema"), ["base"]);
+}
+
+#[tokio::test]
+async fn test_from_url() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::from_url(&format!(
+        "trino://etl%2Bbot@{host}:{port}/tpch/sf1?source=nightly&sessionProperties=a:1;b:2&clientTags=x,y"
+    ))
+    .unwrap()
+    .build()
+    .unwrap();
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(header(&requests[0], "X-Trino-User"), ["etl+bot"]);
+    assert_eq!(header(&requests[0], "X-Trino-Catalog"), ["tpch"]);
+    assert_eq!(header(&requests[0], "X-Trino-Schema"), ["sf1"]);
+    assert_eq!(header(&requests[0], "X-Trino-Source"), ["nightly"]);
+    let mut session = header(&requests[0], "X-Trino-Session");
+    session.sort();
+    assert_eq!(session, ["a=1", "b=2"]);
+    let mut tags: Vec<_> = header(&requests[0], "X-Trino-Client-Tags")[0]
+        .split(',')
+        .collect();
+    tags.sort();
+    assert_eq!(tags, ["x", "y"]);
+}
+
+#[tokio::test]
+async fn test_from_config_profile() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .mount(&server)
+        .await;
+
+    let file = std::env::temp_dir().join(format!("trino_config_{}", std::process::id()));
+    fs::write(
+        &file,
+        format!(
+            "user = alice\ncatalog = tpch\n\n[default]\nserver = unused:1\n\n[local]\nserver = {host}:{port}\nschema = sf1\nclient-tags = x\n"
+        ),
+    )
+    .unwrap();
+    let cli = ClientBuilder::from_config_profile(&file, "local")
+        .unwrap()
+        .build()
+        .unwrap();
+    fs::remove_file(&file).unwrap();
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(header(&requests[0], "X-Trino-User"), ["alice"]);
+    assert_eq!(header(&requests[0], "X-Trino-Catalog"), ["tpch"]);
+    assert_eq!(header(&requests[0], "X-Trino-Schema"), ["sf1"]);
+    assert_eq!(header(&requests[0], "X-Trino-Client-Tags"), ["x"]);
+}
@@ -0,0 +1,128 @@
+use std::fs;
+
+use trino_rust_client::client::ClientBuilder;
+use trino_rust_client::Row;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The coordinator host does not resolve, so requests only succeed when they
+// go through the proxy (the mock server, which answers for any host).
+const UNREACHABLE_HOST: &str = "trino.invalid";
+
+#[tokio::test]
+async fn test_requests_go_through_proxy() {
+    let proxy = MockServer::start().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    // "alice:secret"
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(header("Proxy-Authorization", "Basic YWxpY2U6c2VjcmV0"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", UNREACHABLE_HOST)
+        .port(8080)
+        .proxy(proxy.uri())
+        .proxy_basic_auth("alice", "secret")
+        .build()
+        .unwrap();
+
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+
+    let requests = proxy.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.host_str(), Some(UNREACHABLE_HOST));
+}
+
+#[tokio::test]
+async fn test_no_proxy_bypasses_proxy() {
+    let proxy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&proxy)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", UNREACHABLE_HOST)
+        .port(8080)
+        .proxy(proxy.uri())
+        .no_proxy(UNREACHABLE_HOST)
+        .max_attempt(1)
+        .build()
+        .unwrap();
+
+    assert!(cli.get_all::<Row>("SELECT 1").await.is_err());
+}
+
+#[test]
+fn test_no_proxy_needs_a_proxy() {
+    let err = ClientBuilder::new("test_user", UNREACHABLE_HOST)
+        .no_proxy("internal.example.com")
+        .build()
+        .err()
+        .unwrap();
+    assert!(
+        matches!(err, trino_rust_client::error::Error::InvalidConfig(_)),
+        "got {err:?}"
+    );
+}
+
+#[cfg(feature = "spooling")]
+#[tokio::test]
+async fn test_segments_go_through_segment_proxy() {
+    use serde_json::json;
+    use trino_rust_client::proxy::Proxy;
+
+    const STORAGE_HOST: &str = "storage.invalid";
+
+    let proxy = MockServer::start().await;
+    let segment_proxy = MockServer::start().await;
+    let finished: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string("tests/data/models/query_result_finished").unwrap(),
+    )
+    .unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "s", "infoUri": "http://trino.invalid/ui",
+            "columns": [
+                { "name": "x", "type": "integer", "typeSignature": { "rawType": "integer", "arguments": [] } }
+            ],
+            "data": {
+                "encoding": "json",
+                "segments": [{
+                    "type": "spooled",
+                    "uri": format!("http://{STORAGE_HOST}/segment"),
+                    "metadata": {}
+                }]
+            },
+            "stats": finished["stats"], "warnings": []
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/segment"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[[7]]"))
+        .expect(1)
+        .mount(&segment_proxy)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", UNREACHABLE_HOST)
+        .port(8080)
+        .proxy(proxy.uri())
+        .segment_proxy(Some(Proxy::new(segment_proxy.uri())))
+        .build()
+        .unwrap();
+
+    let rows = cli.get_all::<Row>("SELECT 7").await.unwrap().into_vec();
+    assert_eq!(rows.len(), 1);
+
+    // The coordinator proxy saw the statement only, the segment proxy the
+    // segment only.
+    let requests = proxy.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let requests = segment_proxy.received_requests().await.unwrap();
+    assert_eq!(requests[0].url.host_str(), Some(STORAGE_HOST));
+}
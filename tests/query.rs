@@ -0,0 +1,99 @@
+use std::fs;
+
+use trino_rust_client::{client::ClientBuilder, Row, Trino};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn make_mock_server() -> (MockServer, String, u16) {
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let host_port = uri.trim_start_matches("http://");
+    let (host, port_str) = host_port.rsplit_once(':').unwrap();
+    let port: u16 = port_str.parse().unwrap();
+    (server, host.to_string(), port)
+}
+
+#[derive(Trino)]
+struct Point {
+    x: i32,
+    label: String,
+}
+
+#[tokio::test]
+async fn test_bound_query_is_sent_as_execute_using() {
+    let (server, host, port) = make_mock_server().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .build()
+        .unwrap();
+    let rows = cli
+        .query("SELECT * FROM t WHERE name = ? AND id > ? AND p = ?")
+        .bind("it's".to_string())
+        .bind(Some(10_i64))
+        .bind(Point {
+            x: 1,
+            label: "a".into(),
+        })
+        .get_all::<Row>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let requests = server.received_requests().await.unwrap();
+    let body = String::from_utf8(requests[0].body.clone()).unwrap();
+    let (name, using) = body
+        .strip_prefix("EXECUTE ")
+        .and_then(|rest| rest.split_once(" USING "))
+        .expect("body should be an EXECUTE ... USING statement");
+    assert_eq!(
+        using,
+        "'it''s', BIGINT '10', CAST(ROW(1, 'a') AS row(x integer,label varchar))"
+    );
+
+    // The statement definition rides along in the request header only.
+    let header = requests[0]
+        .headers
+        .get("X-Trino-Prepared-Statement")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    let (k, v) = url::form_urlencoded::parse(header.as_bytes())
+        .next()
+        .unwrap();
+    assert_eq!(k, name);
+    assert_eq!(v, "SELECT * FROM t WHERE name = ? AND id > ? AND p = ?");
+}
+
+#[tokio::test]
+async fn test_unbindable_value_fails_before_sending() {
+    let (server, host, port) = make_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .build()
+        .unwrap();
+    let err = cli
+        .query("SELECT ?")
+        .bind(f64::NAN)
+        .execute()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, trino_rust_client::error::Error::InvalidParameter(_)),
+        "got {err:?}"
+    );
+}
@@ -0,0 +1,106 @@
+use std::fs;
+
+use trino_rust_client::auth::Auth;
+use trino_rust_client::client::ClientBuilder;
+use trino_rust_client::error::Error;
+use trino_rust_client::redirect::RedirectPolicy;
+use trino_rust_client::Row;
+use wiremock::matchers::{body_string, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn make_mock_server() -> (MockServer, String, u16) {
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let host_port = uri.trim_start_matches("http://");
+    let (host, port_str) = host_port.rsplit_once(':').unwrap();
+    let port: u16 = port_str.parse().unwrap();
+    (server, host.to_string(), port)
+}
+
+/// A gateway on 127.0.0.1 redirecting statements to a coordinator reached as
+/// `localhost`, i.e. a different host.
+async fn gateway_and_coordinator() -> (MockServer, String, u16, MockServer) {
+    let (gateway, host, port) = make_mock_server().await;
+    let coordinator = MockServer::start().await;
+    let location = format!(
+        "http://localhost:{}/v1/statement",
+        coordinator.address().port()
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(307).insert_header("Location", location))
+        .mount(&gateway)
+        .await;
+    (gateway, host, port, coordinator)
+}
+
+#[tokio::test]
+async fn test_follows_redirect_to_trusted_host() {
+    let (_gateway, host, port, coordinator) = gateway_and_coordinator().await;
+    let finished = fs::read_to_string("tests/data/models/query_result_finished").unwrap();
+    // "user:secret"
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(body_string("SELECT 1"))
+        .and(header("Authorization", "Basic dXNlcjpzZWNyZXQ="))
+        .respond_with(ResponseTemplate::new(200).set_body_string(finished))
+        .expect(1)
+        .mount(&coordinator)
+        .await;
+
+    let cli = ClientBuilder::new("user", host)
+        .port(port)
+        .auth(Auth::new_basic("user", Some("secret")))
+        .auth_http_insecure(true)
+        .redirect_policy(RedirectPolicy {
+            trusted_hosts: vec!["localhost".to_string()],
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    cli.get_all::<Row>("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_refuses_redirect_to_untrusted_host() {
+    let (_gateway, host, port, coordinator) = gateway_and_coordinator().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&coordinator)
+        .await;
+
+    let cli = ClientBuilder::new("user", host)
+        .port(port)
+        .auth(Auth::new_basic("user", Some("secret")))
+        .auth_http_insecure(true)
+        .build()
+        .unwrap();
+
+    let err = cli.get_all::<Row>("SELECT 1").await.unwrap_err();
+    assert!(matches!(err, Error::Redirect(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_redirect_loop_is_bounded() {
+    let (server, host, port) = make_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(307).insert_header("Location", "/v1/statement"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let cli = ClientBuilder::new("user", host)
+        .port(port)
+        .redirect_policy(RedirectPolicy {
+            max_redirects: 2,
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+
+    let err = cli.get_all::<Row>("SELECT 1").await.unwrap_err();
+    assert!(matches!(err, Error::Redirect(_)), "{err:?}");
+}
@@ -104,6 +104,105 @@ async fn test_stream_yields_rows_across_pages() {
     assert_eq!(rows.len(), 3, "expected 3 rows streamed across 3 pages");
 }
 
+// Streamed rows carry the column types of their result, like those of
+// `get_all`, so they can be decoded by name.
+#[tokio::test]
+async fn test_stream_rows_decode_by_column() {
+    let (server, host, port) = make_mock_server().await;
+    mount_paged_result(&server).await;
+
+    let cli = client(host, port);
+    let mut stream = cli.stream::<Row>("SELECT * FROM t").await.unwrap();
+    let mut count = 0;
+    while let Some(row) = stream.next().await {
+        let row = row.unwrap();
+        assert_eq!(row.get::<String>("a").unwrap(), "abc");
+        assert_eq!(row.get::<i32>("b").unwrap(), 10);
+        assert_eq!(row.get::<Vec<i32>>("d").unwrap(), vec![1, 2, 3]);
+        assert_eq!(row.columns().unwrap().len(), 6);
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[tokio::test]
+async fn test_stream_decode_error_locates_value() {
+    use serde::Deserialize;
+    use trino_rust_client::error::Error;
+    use trino_rust_client::Trino;
+
+    #[derive(Trino, Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct A {
+        a: String,
+        b: i32,
+        c: bool,
+        d: Vec<i32>,
+        e: B,
+        f: Option<String>,
+    }
+
+    #[derive(Trino, Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct B {
+        x: i64,
+        y: f64,
+    }
+
+    let (server, host, port) = make_mock_server().await;
+    let uri = server.uri();
+    let finished = read_fixture("query_result_finished");
+    let stats = finished["stats"].clone();
+    let row = finished["data"][0].clone();
+    let mut bad = row.clone();
+    bad[1] = json!("ten");
+
+    mount(
+        &server,
+        "POST",
+        "",
+        json!({
+            "id": "q", "infoUri": format!("{uri}/ui"),
+            "nextUri": format!("{uri}/v1/statement/q/1"),
+            "columns": finished["columns"].clone(), "data": [row.clone()],
+            "stats": stats.clone(), "warnings": []
+        }),
+    )
+    .await;
+    mount(
+        &server,
+        "GET",
+        "/v1/statement/q/1",
+        json!({
+            "id": "q", "infoUri": format!("{uri}/ui"),
+            "columns": finished["columns"].clone(), "data": [row, bad],
+            "stats": stats, "warnings": []
+        }),
+    )
+    .await;
+
+    let cli = client(host, port);
+    let mut stream = cli.stream::<A>("SELECT * FROM t").await.unwrap();
+    // the first page decodes, the second fails as a whole
+    stream.next().await.unwrap().unwrap();
+    let err = match stream.next().await {
+        Some(Err(Error::RowDecode(err))) => err,
+        other => panic!("expected a decode error, got {:?}", other),
+    };
+    assert_eq!(err.page, Some(1));
+    assert_eq!(err.row, Some(1));
+    assert_eq!(err.column.as_deref(), Some("b"));
+    assert_eq!(err.column_index, Some(1));
+    assert_eq!(err.trino_type.as_deref(), Some("integer"));
+    assert_eq!(err.rust_type, Some("i32"));
+    assert!(
+        err.to_string()
+            .starts_with("page 1, row 1, column b (#1, integer as i32): "),
+        "{}",
+        err
+    );
+}
+
 // A RowStream must be `Send` so it can be held across `.await` inside a
 // spawned task (a very common pattern in async services). This test would fail
 // to compile if the inner boxed stream were not `Send`.
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::RootCertStore;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use trino_rust_client::client::ClientBuilder;
+use trino_rust_client::ssl::Ssl;
+
+// SPKI pin of tests/data/tls/server.crt.
+const SERVER_PIN: &str = "G2TOoGmUMrWd+HP+iXZlT3F0q0Qee8YyRKx+YBIEItk=";
+
+/// A TLS server for `localhost`, signed by tests/data/tls/ca.crt, answering
+/// every request with `204 No Content`.
+async fn start_tls_server() -> u16 {
+    start_server("server", false, NO_CONTENT).await
+}
+
+/// Like [`start_tls_server`], but requiring a client certificate issued by
+/// tests/data/tls/client_ca.crt.
+async fn start_mtls_server() -> u16 {
+    start_server("server", true, NO_CONTENT).await
+}
+
+/// Like [`start_tls_server`], but with a certificate for `trino.invalid`
+/// signed by tests/data/tls/client_ca.crt.
+async fn start_other_host_server() -> u16 {
+    start_server("other_host", false, NO_CONTENT).await
+}
+
+const NO_CONTENT: &[u8] = b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n";
+
+/// Serves tests/data/tls/`cert`.crt with its `.key`.
+async fn start_server(cert: &str, client_auth: bool, response: &'static [u8]) -> u16 {
+    let certs = CertificateDer::pem_file_iter(format!("tests/data/tls/{cert}.crt"))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_file(format!("tests/data/tls/{cert}.key")).unwrap();
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .unwrap();
+    let builder = if client_auth {
+        let mut roots = RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter("tests/data/tls/client_ca.crt").unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .unwrap();
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let config = builder.with_single_cert(certs, key).unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream.write_all(response).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    port
+}
+
+fn ssl(pins: &[&str]) -> Ssl {
+    Ssl {
+        root_certs: Ssl::read_pem_bundle(&"tests/data/tls/ca.crt").unwrap(),
+        built_in_roots: false,
+        spki_pins: pins.iter().map(|p| p.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_custom_roots_only() {
+    let port = start_tls_server().await;
+    let cli = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(&[]))
+        .build()
+        .unwrap();
+    cli.cancel("q").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_spki_pinning() {
+    let port = start_tls_server().await;
+    let pinned = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(&[
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            SERVER_PIN,
+        ]))
+        .build()
+        .unwrap();
+    pinned.cancel("q").await.unwrap();
+
+    // A valid chain with the wrong key is still rejected.
+    let mispinned = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(&["AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="]))
+        .build()
+        .unwrap();
+    assert!(mispinned.cancel("q").await.is_err());
+}
+
+// SPKI pin of the leaf of tests/data/tls/unpinned.crt.
+const UNPINNED_PIN: &str = "Hxkm7CSarKZXrVV+2pA6zS/0vtdjuYXzoSfMnDc313E=";
+
+// A server with another valid certificate cannot pass the pin check by
+// appending the pinned certificate as an unused intermediate.
+#[tokio::test]
+async fn test_pin_is_checked_on_the_server_certificate() {
+    // tests/data/tls/unpinned.crt: a `localhost` leaf signed by
+    // client_ca.crt, followed by server.crt.
+    let port = start_server("unpinned", false, NO_CONTENT).await;
+    let ssl = |pin: &str| Ssl {
+        root_certs: Ssl::read_pem_bundle(&"tests/data/tls/client_ca.crt").unwrap(),
+        built_in_roots: false,
+        spki_pins: vec![pin.to_string()],
+        ..Default::default()
+    };
+    let mispinned = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(SERVER_PIN))
+        .build()
+        .unwrap();
+    assert!(mispinned.cancel("q").await.is_err());
+
+    let pinned = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(UNPINNED_PIN))
+        .build()
+        .unwrap();
+    pinned.cancel("q").await.unwrap();
+}
+
+#[test]
+fn test_invalid_pin_is_rejected_at_build() {
+    let err = ClientBuilder::new("test_user", "localhost")
+        .secure(true)
+        .ssl(ssl(&["not a digest"]))
+        .build()
+        .err()
+        .unwrap();
+    assert!(
+        matches!(err, trino_rust_client::error::Error::Tls(_)),
+        "got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_client_certificate() {
+    let port = start_mtls_server().await;
+    let identity = Ssl::read_identity_pkcs12(&"tests/data/tls/identity.p12", "trino").unwrap();
+    // Both with the TLS setup reqwest builds and with the pinned one.
+    for pins in [&[][..], &[SERVER_PIN]] {
+        let cli = ClientBuilder::new("test_user", "localhost")
+            .port(port)
+            .secure(true)
+            .ssl(Ssl {
+                client_identity: Some(identity.clone()),
+                ..ssl(pins)
+            })
+            .build()
+            .unwrap();
+        cli.cancel("q").await.unwrap();
+    }
+
+    let anonymous = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl(&[]))
+        .build()
+        .unwrap();
+    assert!(anonymous.cancel("q").await.is_err());
+}
+
+#[tokio::test]
+async fn test_chain_only_verification() {
+    let port = start_other_host_server().await;
+    let ssl = Ssl {
+        root_certs: Ssl::read_pem_bundle(&"tests/data/tls/client_ca.crt").unwrap(),
+        built_in_roots: false,
+        ..Default::default()
+    };
+    let strict = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(ssl.clone())
+        .build()
+        .unwrap();
+    assert!(strict.cancel("q").await.is_err());
+
+    let chain_only = ClientBuilder::new("test_user", "localhost")
+        .port(port)
+        .secure(true)
+        .ssl(Ssl {
+            verify_hostname: false,
+            ..ssl.clone()
+        })
+        .build()
+        .unwrap();
+    chain_only.cancel("q").await.unwrap();
+
+    // The chain is still checked: a server signed by another CA is rejected.
+    let untrusted = ClientBuilder::new("test_user", "localhost")
+        .port(start_tls_server().await)
+        .secure(true)
+        .ssl(Ssl {
+            verify_hostname: false,
+            ..ssl
+        })
+        .build()
+        .unwrap();
+    assert!(untrusted.cancel("q").await.is_err());
+}
+
+#[tokio::test]
+async fn test_ssl_verification_ca_in_url() {
+    let port = start_other_host_server().await;
+    let url = format!(
+        "trino://test_user@localhost:{port}?SSL=true&SSLVerification=CA&SSLTrustStorePath=tests/data/tls/client_ca.crt"
+    );
+    let cli = ClientBuilder::from_url(&url).unwrap().build().unwrap();
+    cli.cancel("q").await.unwrap();
+
+    let full = url.replace("=CA", "=FULL");
+    let cli = ClientBuilder::from_url(&full).unwrap().build().unwrap();
+    assert!(cli.cancel("q").await.is_err());
+}
+
+// Pins and the client identity belong to the coordinator: a spooled segment on
+// other storage is downloaded with the extra roots alone.
+#[cfg(feature = "spooling")]
+#[tokio::test]
+async fn test_segment_download_ignores_coordinator_pins() {
+    use serde_json::json;
+    use trino_rust_client::Row;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let storage = start_server(
+        "server",
+        false,
+        b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 5\r\n\r\n[[7]]",
+    )
+    .await;
+    let coordinator = MockServer::start().await;
+    let uri = coordinator.uri();
+    let finished: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string("tests/data/models/query_result_finished").unwrap(),
+    )
+    .unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "s", "infoUri": format!("{uri}/ui"),
+            "columns": [
+                { "name": "x", "type": "integer", "typeSignature": { "rawType": "integer", "arguments": [] } }
+            ],
+            "data": {
+                "encoding": "json",
+                "segments": [{
+                    "type": "spooled",
+                    "uri": format!("https://localhost:{storage}/segment"),
+                    "metadata": {}
+                }]
+            },
+            "stats": finished["stats"], "warnings": []
+        })))
+        .mount(&coordinator)
+        .await;
+
+    let url = reqwest::Url::parse(&uri).unwrap();
+    let identity = Ssl::read_identity_pkcs12(&"tests/data/tls/identity.p12", "trino").unwrap();
+    let cli = ClientBuilder::new("test_user", url.host_str().unwrap())
+        .port(url.port().unwrap())
+        .ssl(Ssl {
+            client_identity: Some(identity),
+            ..ssl(&["AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="])
+        })
+        .build()
+        .unwrap();
+    let rows = cli.get_all::<Row>("SELECT 7").await.unwrap().into_vec();
+    assert_eq!(rows.len(), 1);
+}
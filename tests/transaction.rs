@@ -0,0 +1,156 @@
+use std::fs;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use trino_rust_client::client::ClientBuilder;
+use wiremock::matchers::{body_string, method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+async fn make_mock_server() -> (MockServer, String, u16) {
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let host_port = uri.trim_start_matches("http://");
+    let (host, port_str) = host_port.rsplit_once(':').unwrap();
+    let port: u16 = port_str.parse().unwrap();
+    (server, host.to_string(), port)
+}
+
+fn stats() -> Value {
+    let finished: Value = serde_json::from_str(
+        &fs::read_to_string("tests/data/models/query_result_finished").unwrap(),
+    )
+    .unwrap();
+    finished["stats"].clone()
+}
+
+/// Mount a two-page statement: the POST of `sql` points at `/v1/statement/{id}/1`,
+/// which finishes the query with `update_type` and the given response header.
+async fn mount_statement(
+    server: &MockServer,
+    sql: &str,
+    id: &str,
+    update_type: &str,
+    header: Option<(&str, &str)>,
+) {
+    let uri = server.uri();
+    Mock::given(method("POST"))
+        .and(path("/v1/statement"))
+        .and(body_string(sql))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": id, "infoUri": format!("{uri}/ui"),
+            "nextUri": format!("{uri}/v1/statement/{id}/1"),
+            "stats": stats(), "warnings": []
+        })))
+        .mount(server)
+        .await;
+
+    let mut finished = ResponseTemplate::new(200).set_body_json(json!({
+        "id": id, "infoUri": format!("{uri}/ui"),
+        "stats": stats(), "warnings": [], "updateType": update_type
+    }));
+    if let Some((k, v)) = header {
+        finished = finished.insert_header(k, v);
+    }
+    Mock::given(method("GET"))
+        .and(path(format!("/v1/statement/{id}/1")))
+        .respond_with(finished)
+        .mount(server)
+        .await;
+}
+
+fn transaction_header(requests: &[Request], sql: &str) -> String {
+    let req = requests
+        .iter()
+        .find(|r| r.body == sql.as_bytes())
+        .unwrap_or_else(|| panic!("no request for {sql}"));
+    req.headers
+        .get("X-Trino-Transaction-Id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_transaction_scopes_statements_and_commits() {
+    let (server, host, port) = make_mock_server().await;
+    mount_statement(
+        &server,
+        "START TRANSACTION",
+        "start",
+        "START TRANSACTION",
+        Some(("X-Trino-Started-Transaction-Id", "tx-1")),
+    )
+    .await;
+    mount_statement(&server, "INSERT INTO t VALUES 1", "ins", "INSERT", None).await;
+    mount_statement(&server, "DELETE FROM t", "del", "DELETE", None).await;
+    mount_statement(
+        &server,
+        "COMMIT",
+        "commit",
+        "COMMIT",
+        Some(("X-Trino-Clear-Transaction-Id", "true")),
+    )
+    .await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .build()
+        .unwrap();
+    let tx = cli.begin_transaction().await.unwrap();
+    assert_eq!(tx.id(), "tx-1");
+
+    tx.execute("INSERT INTO t VALUES 1").await.unwrap();
+    // A statement on the client itself stays outside the transaction.
+    cli.execute("DELETE FROM t").await.unwrap();
+    tx.commit().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(transaction_header(&requests, "START TRANSACTION"), "NONE");
+    assert_eq!(
+        transaction_header(&requests, "INSERT INTO t VALUES 1"),
+        "tx-1"
+    );
+    assert_eq!(transaction_header(&requests, "DELETE FROM t"), "NONE");
+    assert_eq!(transaction_header(&requests, "COMMIT"), "tx-1");
+    assert!(
+        !requests.iter().any(|r| r.body == b"ROLLBACK"),
+        "a committed transaction must not be rolled back on drop"
+    );
+}
+
+#[tokio::test]
+async fn test_dropped_transaction_rolls_back() {
+    let (server, host, port) = make_mock_server().await;
+    mount_statement(
+        &server,
+        "START TRANSACTION",
+        "start",
+        "START TRANSACTION",
+        Some(("X-Trino-Started-Transaction-Id", "tx-2")),
+    )
+    .await;
+    mount_statement(&server, "ROLLBACK", "rb", "ROLLBACK", None).await;
+
+    let cli = ClientBuilder::new("test_user", host)
+        .port(port)
+        .build()
+        .unwrap();
+    drop(cli.begin_transaction().await.unwrap());
+
+    // The rollback is fire-and-forget; give the spawned task time to run.
+    let mut rolled_back = false;
+    for _ in 0..50 {
+        let requests = server.received_requests().await.unwrap();
+        if requests
+            .iter()
+            .any(|r| r.url.path() == "/v1/statement/rb/1")
+        {
+            assert_eq!(transaction_header(&requests, "ROLLBACK"), "tx-2");
+            rolled_back = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(rolled_back, "dropping the transaction should roll it back");
+}
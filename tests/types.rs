@@ -38,17 +38,67 @@ fn decodes_varbinary_column() {
     assert_eq!(rows[0].n, 42);
 }
 
-// An unsupported Trino type must fail with an error that names the type,
-// rather than a generic "invalid type signature".
+// A type signature the client cannot map (here a `qdigest` missing its value
+// type) must fail with an error that names the type, rather than a generic
+// "invalid type signature".
 #[test]
 fn unsupported_type_error_names_the_type() {
     use trino_rust_client::{TrinoTy, TypeSignature};
 
     let sig: TypeSignature =
-        serde_json::from_str(r#"{ "rawType": "HyperLogLog", "arguments": [] }"#).unwrap();
+        serde_json::from_str(r#"{ "rawType": "qdigest", "arguments": [] }"#).unwrap();
     let err = TrinoTy::from_type_signature(sig).unwrap_err();
     assert!(
-        err.to_string().contains("HyperLogLog"),
+        err.to_string().contains("qdigest"),
         "error should name the type, got: {err}"
     );
 }
+
+// Temporal types carry their precision; servers that send none mean
+// milliseconds.
+#[test]
+fn temporal_types_carry_precision() {
+    use trino_rust_client::{TrinoTy, TypeSignature};
+
+    let ty = |json: &str| {
+        let sig: TypeSignature = serde_json::from_str(json).unwrap();
+        TrinoTy::from_type_signature(sig).unwrap()
+    };
+    assert_eq!(
+        ty(r#"{ "rawType": "timestamp", "arguments": [{ "kind": "LONG", "value": 6 }] }"#),
+        TrinoTy::Timestamp(6)
+    );
+    assert_eq!(
+        ty(
+            r#"{ "rawType": "time with time zone", "arguments": [{ "kind": "LONG", "value": 12 }] }"#
+        ),
+        TrinoTy::TimeWithTimeZone(12)
+    );
+    assert_eq!(
+        ty(r#"{ "rawType": "timestamp", "arguments": [] }"#),
+        TrinoTy::Timestamp(3)
+    );
+    assert_eq!(
+        TrinoTy::TimestampWithTimeZone(9).full_type(),
+        "timestamp(9) with time zone"
+    );
+    assert_eq!(TrinoTy::Timestamp(3).full_type(), "timestamp");
+    for raw in [
+        "time",
+        "time with time zone",
+        "timestamp",
+        "timestamp with time zone",
+    ] {
+        let sig: TypeSignature = serde_json::from_str(&format!(
+            r#"{{ "rawType": "{raw}", "arguments": [{{ "kind": "LONG", "value": 13 }}] }}"#
+        ))
+        .unwrap();
+        assert!(
+            matches!(
+                TrinoTy::from_type_signature(sig),
+                Err(trino_rust_client::types::Error::InvalidTypeSignature)
+            ),
+            "{raw}(13) should be rejected"
+        );
+    }
+}
@@ -1,66 +1,269 @@
 use proc_macro2::*;
 use quote::*;
-use structmeta::StructMeta;
+use structmeta::{Flag, StructMeta};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 use syn::*;
 
 #[derive(StructMeta, Debug)]
-struct TrinoAttributes {
-    rename: LitStr,
+struct ContainerAttributes {
+    rename_all: Option<LitStr>,
+    lenient: Flag,
+}
+
+#[derive(StructMeta, Debug)]
+struct FieldAttributes {
+    rename: Option<LitStr>,
+    default: Flag,
+    skip: Flag,
+    flatten: Flag,
+    with: Option<LitStr>,
 }
 
 /// Derive macro to parse and represent a Trino result table.
 ///
-/// The following arguments are supported:
+/// Structs with named fields take the columns of the same name, tuple structs take the
+/// columns by position. C-like enums are decoded from `varchar` values, by variant name.
+///
+/// The following field arguments are supported:
 ///
 /// * `rename`: Specify a custom field name in the result table. This is useful in case the returned
 ///   columns contain spaces (e.g. `Query Plan`).
 ///   This is the equivalent to to `#[serde(rename = "name")]`.
+/// * `default`: The column may be missing from the result, the field is then set to
+///   `Default::default()`.
+/// * `skip`: The field is not a column, it is always set to `Default::default()`.
+/// * `flatten`: The field is another `#[derive(Trino)]` struct whose columns are part of this one,
+///   instead of a single `row` column.
+/// * `with`: Decode the column with the functions of a module instead of the [`Trino`] impl of the
+///   field type: `ty() -> TrinoTy` giving the column type, and `serialize` / `deserialize` as for
+///   `#[serde(with = "module")]`. The field type must implement `Default`.
+///
+/// The following container arguments are supported:
+///
+/// * `rename_all`: Rename every field, with the same rules as `#[serde(rename_all = "...")]`
+///   (e.g. `"camelCase"`).
+/// * `lenient`: Ignore the columns of the result that have no field, and match column names
+///   case-insensitively, instead of failing on any difference.
+///
+/// Enum variants support `rename`, and enums `rename_all`.
 #[proc_macro_derive(Trino, attributes(trino))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let data = parse_macro_input!(input as ItemStruct);
-
-    match derive_impl(data) {
+    let data = parse_macro_input!(input as Item);
+
+    let ret = match data {
+        Item::Struct(data) => derive_impl(data),
+        Item::Enum(data) => derive_enum(data),
+        data => Err(Error::new(
+            data.span(),
+            "only structs and enums can derive Trino",
+        )),
+    };
+    match ret {
         Ok(d) => d.into(),
         Err(e) => e.to_compile_error().into(),
     }
 }
 
+/// How a field maps to the columns of the result.
+enum Kind {
+    Column,
+    Default,
+    With { module: Path, default: bool },
+    Flatten,
+    Skip,
+}
+
+struct FieldInfo<'a> {
+    member: Member,
+    ty: &'a Type,
+    name: LitStr,
+    kind: Kind,
+}
+
+impl FieldInfo<'_> {
+    fn is_column(&self) -> bool {
+        !matches!(self.kind, Kind::Skip)
+    }
+
+    /// The number of columns of the field.
+    fn width(&self) -> TokenStream {
+        let ty = self.ty;
+        match self.kind {
+            Kind::Flatten => quote!(<#ty as ::trino_rust_client::types::TrinoFields>::WIDTH),
+            _ => quote!(1usize),
+        }
+    }
+}
+
+fn parse_attrs<T: syn::parse::Parse>(attrs: &[Attribute]) -> Result<Option<T>> {
+    let mut attrs = attrs.iter().filter(|attr| attr.path().is_ident("trino"));
+    let ret = attrs.next().map(|attr| attr.parse_args()).transpose()?;
+    if let Some(attr) = attrs.next() {
+        return Err(Error::new(attr.span(), "duplicate trino attribute"));
+    }
+    Ok(ret)
+}
+
+fn field_info<'a>(
+    field: &'a Field,
+    index: usize,
+    rename_all: Option<RenameRule>,
+) -> Result<FieldInfo<'a>> {
+    let Some(ident) = &field.ident else {
+        // tuple struct fields are the columns by position
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("trino"))
+        {
+            return Err(Error::new(
+                attr.span(),
+                "tuple structs do not support trino attributes",
+            ));
+        }
+        return Ok(FieldInfo {
+            member: Member::Unnamed(Index::from(index)),
+            ty: &field.ty,
+            name: LitStr::new(&format!("_col{}", index), field.span()),
+            kind: Kind::Column,
+        });
+    };
+    let attrs: Option<FieldAttributes> = parse_attrs(&field.attrs)?;
+
+    let mut name = ident.unraw().to_string();
+    if let Some(rule) = rename_all {
+        name = rule.apply(&name);
+    }
+    let mut kind = Kind::Column;
+
+    if let Some(attrs) = attrs {
+        if let Some(span) = attrs.skip.span {
+            if attrs.rename.is_some() || attrs.with.is_some() || attrs.flatten.value() {
+                return Err(Error::new(
+                    span,
+                    "skip can not be combined with rename, with or flatten",
+                ));
+            }
+            kind = Kind::Skip;
+        } else if let Some(span) = attrs.flatten.span {
+            if attrs.rename.is_some() || attrs.with.is_some() || attrs.default.value() {
+                return Err(Error::new(
+                    span,
+                    "flatten can not be combined with rename, with or default",
+                ));
+            }
+            kind = Kind::Flatten;
+        } else if let Some(with) = &attrs.with {
+            kind = Kind::With {
+                module: with.parse()?,
+                default: attrs.default.value(),
+            };
+        } else if attrs.default.value() {
+            kind = Kind::Default;
+        }
+
+        if let Some(rename) = &attrs.rename {
+            name = rename.value();
+        }
+    }
+
+    Ok(FieldInfo {
+        member: Member::Named(ident.clone()),
+        ty: &field.ty,
+        name: LitStr::new(&name, ident.span()),
+        kind,
+    })
+}
+
 fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
     let name = &data.ident;
     let vis = &data.vis;
     let seed_name = format_ident!("__{}Seed", name);
-    let fields: Vec<Field> = match data.fields {
-        Fields::Named(d) => d.named.into_iter().collect(),
-        Fields::Unnamed(d) => return Err(Error::new(d.span(), "field must be named")),
+    let tuple = match &data.fields {
+        Fields::Named(_) => false,
+        Fields::Unnamed(f) if f.unnamed.is_empty() => {
+            return Err(Error::new(data.span(), "field can not be empty"))
+        }
+        Fields::Unnamed(_) => true,
         Fields::Unit => return Err(Error::new(data.span(), "field can not be unit")),
     };
+    if tuple {
+        if let Some(attr) = data.attrs.iter().find(|attr| attr.path().is_ident("trino")) {
+            return Err(Error::new(
+                attr.span(),
+                "tuple structs do not support trino attributes",
+            ));
+        }
+    }
 
-    let tuplety = Ident::new(&format!("Tuple{}", fields.len()), Span::call_site());
-
-    let keys = fields.iter().map(|f| f.ident.as_ref().unwrap());
-    let keys_lit = fields.iter().map(|field| {
-        let ident = field.ident.as_ref().unwrap();
-        let attrs = field
-            .attrs
+    let (rename_all, lenient) = match parse_attrs::<ContainerAttributes>(&data.attrs)? {
+        Some(ContainerAttributes {
+            rename_all,
+            lenient,
+        }) => (
+            rename_all.as_ref().map(RenameRule::parse).transpose()?,
+            lenient.value(),
+        ),
+        None => (None, false),
+    };
+    let ty = if tuple {
+        quote! {
+            ::trino_rust_client::types::TrinoTy::Tuple(types.into_iter().map(|(_, ty)| ty).collect())
+        }
+    } else if lenient {
+        quote! {
+            ::trino_rust_client::types::TrinoTy::Lenient(::std::boxed::Box::new(::trino_rust_client::types::TrinoTy::Row(types)))
+        }
+    } else {
+        quote!(::trino_rust_client::types::TrinoTy::Row(types))
+    };
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| field_info(f, i, rename_all))
+        .collect::<Result<Vec<_>>>()?;
+    let columns: Vec<&FieldInfo> = fields.iter().filter(|f| f.is_column()).collect();
+
+    // Plain columns keep their value as a tuple of the field values, the others
+    // need the columns of flattened fields and the `with` modules.
+    let plain = !columns.is_empty()
+        && columns
             .iter()
-            .filter(|attr| attr.path().is_ident("trino"))
-            .flat_map(|attr| attr.parse_args().ok())
-            .next();
-
-        let field_name = match attrs {
-            Some(TrinoAttributes { rename }) => rename.value(),
-            _ => ident.to_string(),
-        };
+            .all(|f| matches!(f.kind, Kind::Column | Kind::Default));
+
+    let (value_ty, value) = if plain {
+        let tuplety = Ident::new(&format!("Tuple{}", columns.len()), Span::call_site());
+        let keys = columns.iter().map(|f| &f.member);
+        let types = columns.iter().map(|f| f.ty);
+        let types1 = types.clone();
+        (
+            quote! {
+                type ValueType<'_a> where #(#types: '_a ,)* = ::trino_rust_client::tuples::#tuplety< #(<#types1 as ::trino_rust_client::types::Trino>::ValueType<'_a>, )* >;
+            },
+            quote!(::trino_rust_client::tuples::#tuplety( #(::trino_rust_client::types::Trino::value(&self.#keys), )* )),
+        )
+    } else {
+        (
+            quote! {
+                type ValueType<'_a> where Self: '_a = ::trino_rust_client::types::FieldsValue<'_a, Self>;
+            },
+            quote!(::trino_rust_client::types::FieldsValue(self)),
+        )
+    };
 
-        LitStr::new(&field_name, ident.span())
+    let empty = fields.iter().map(|f| {
+        let (ident, ty) = (&f.member, f.ty);
+        match f.kind {
+            Kind::Column | Kind::Flatten => {
+                quote!(#ident: <#ty as ::trino_rust_client::types::Trino>::empty())
+            }
+            Kind::Default | Kind::With { .. } | Kind::Skip => {
+                quote!(#ident: ::std::default::Default::default())
+            }
+        }
     });
-    let types = fields.iter().map(|f| &f.ty);
-    let types1 = types.clone();
-
-    let keys2 = keys.clone();
-    let types2 = types.clone();
-    let types3 = types.clone();
 
     let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
 
@@ -75,46 +278,34 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
     let impl_trait_block = quote! {
 
         impl #impl_generics ::trino_rust_client::types::Trino for #name #ty_generics #where_clause {
-            type ValueType<'_a> where #(#types: '_a ,)* = ::trino_rust_client::tuples::#tuplety< #(<#types1 as ::trino_rust_client::types::Trino>::ValueType<'_a>, )* >;
+            #value_ty
             type Seed<'_a, '_de> = #seed_name #seed_ty_generics;
 
             fn value(&self) -> Self::ValueType<'_>  {
-                ::trino_rust_client::tuples::#tuplety( #(self.#keys.value(), )* )
+                #value
             }
 
             fn ty() -> ::trino_rust_client::types::TrinoTy {
-                let types = vec![ #((#keys_lit.into(), <#types2 as ::trino_rust_client::types::Trino>::ty())),* ];
-                ::trino_rust_client::types::TrinoTy::Row(types)
+                let types = <Self as ::trino_rust_client::types::TrinoFields>::fields();
+                #ty
             }
 
             fn seed<'_a, '_de>(ctx: &'_a ::trino_rust_client::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
-                if let ::trino_rust_client::types::TrinoTy::Row(types)  = ctx.ty() {
-                    let row_map = ctx.row_map().expect("invalid context");
-                    if row_map.len() != types.len() {
-                        panic!("invalid context");
-                    }
-                    #seed_name {
-                        ctx,
-                        types,
-                        row_map,
-                        _marker: ::std::marker::PhantomData,
-                    }
-                } else {
-                    panic!("invalid context")
+                #seed_name {
+                    ctx,
+                    _marker: ::std::marker::PhantomData,
                 }
             }
 
             fn empty() -> Self {
                 Self {
-                    #( #keys2:  <#types3 as ::trino_rust_client::types::Trino>::empty(),)*
+                    #( #empty, )*
                 }
             }
         }
 
         #vis struct #seed_name #seed_impl_generics #where_clause {
             ctx: &'_a ::trino_rust_client::types::Context<'_a>,
-            types: &'_a [(::std::string::String,::trino_rust_client::types::TrinoTy)],
-            row_map: &'_a [usize],
             _marker: ::std::marker::PhantomData<#name #ty_generics>,
         }
 
@@ -137,11 +328,36 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
             }
 
             fn visit_seq<_A: ::serde::de::SeqAccess<'_de>>(self, mut seq: _A) -> Result<Self::Value, _A::Error> {
-                let mut ret = Self::Value::empty();
+                let mut ret = <Self::Value as ::trino_rust_client::types::Trino>::empty();
 
-                for (idx, ty) in self.row_map.iter().zip(self.types.iter().map(|r| &r.1)) {
+                // decode the `i`th value of the sequence into the column at `idx`
+                let mut access = |i: usize, idx: usize, name: &dyn ::std::fmt::Display, ty| {
                     let ctx = self.ctx.with_ty(ty);
-                    ret.__access_seq(*idx, &mut seq, &ctx)?;
+                    ::trino_rust_client::types::TrinoFields::access_seq(&mut ret, idx, &mut seq, &ctx)
+                        .map_err(|e| {
+                            let rust_type = <Self::Value as ::trino_rust_client::types::TrinoFields>::type_name(idx);
+                            ctx.column_error(i, name, rust_type, e)
+                        })
+                };
+                match self.ctx.ty() {
+                    // named fields take the columns by name, as mapped by the
+                    // context, tuple fields take them by position
+                    ::trino_rust_client::types::TrinoTy::Row(types) => {
+                        let row_map = self.ctx.row_map();
+                        if row_map.is_some_and(|row_map| row_map.len() != types.len()) {
+                            panic!("invalid context");
+                        }
+                        for (i, (name, ty)) in types.iter().enumerate() {
+                            access(i, row_map.map_or(i, |row_map| row_map[i]), name, ty)?;
+                        }
+                    }
+                    // the fields of an unnamed row
+                    ::trino_rust_client::types::TrinoTy::Tuple(types) => {
+                        for (i, ty) in types.iter().enumerate() {
+                            access(i, i, &i, ty)?;
+                        }
+                    }
+                    _ => panic!("invalid context"),
                 }
 
                 if let Ok(None) = seq.next_element::<String>() {
@@ -153,7 +369,7 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
         }
     };
 
-    let impl_block = access_seq(&fields, name, &data.generics)?;
+    let impl_block = impl_fields(&columns, name, &data.generics)?;
 
     let ret = quote! {
         #impl_trait_block
@@ -163,34 +379,361 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
     Ok(ret)
 }
 
-fn access_seq(fields: &[Field], name: &Ident, generics: &Generics) -> Result<TokenStream> {
-    let indices = (0..fields.len()).map(|i| LitInt::new(&format!("{}", i), fields[i].span()));
-    let keys = fields.iter().map(|f| f.ident.as_ref().unwrap());
-    let types = fields.iter().map(|f| &f.ty);
+fn impl_fields(columns: &[&FieldInfo], name: &Ident, generics: &Generics) -> Result<TokenStream> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let access_seq = quote! {
-        impl #impl_generics #name #ty_generics #where_clause {
-            fn __access_seq<'_a, '_de, _A: ::serde::de::SeqAccess<'_de>>(&mut self, idx: usize, seq: &mut _A, ctx: &'_a ::trino_rust_client::types::Context<'_a>)
+    // The generics of the helper types of `with` fields, declared inside the
+    // methods of the impl.
+    let mut with_generics = generics.clone();
+    with_generics.params.insert(0, parse_quote!('_w));
+    let (with_impl_generics, with_ty_generics, _) = with_generics.split_for_impl();
+    let mut with_de_generics = generics.clone();
+    with_de_generics.params.insert(0, parse_quote!('_wde));
+    let (with_de_impl_generics, _, _) = with_de_generics.split_for_impl();
+
+    let width = columns.iter().map(|f| f.width());
+
+    let fields = columns.iter().map(|f| {
+        let (ty, name) = (f.ty, &f.name);
+        match &f.kind {
+            Kind::Column => quote! {
+                fields.push((#name.into(), <#ty as ::trino_rust_client::types::Trino>::ty()));
+            },
+            Kind::Default => quote! {
+                fields.push((#name.into(), ::trino_rust_client::types::TrinoTy::Defaulted(::std::boxed::Box::new(<#ty as ::trino_rust_client::types::Trino>::ty()))));
+            },
+            Kind::With { module, default: false } => quote! {
+                fields.push((#name.into(), #module::ty()));
+            },
+            Kind::With { module, default: true } => quote! {
+                fields.push((#name.into(), ::trino_rust_client::types::TrinoTy::Defaulted(::std::boxed::Box::new(#module::ty()))));
+            },
+            Kind::Flatten => quote! {
+                fields.extend(<#ty as ::trino_rust_client::types::TrinoFields>::fields());
+            },
+            Kind::Skip => unreachable!(),
+        }
+    });
+
+    let serialize = columns.iter().map(|f| {
+        let (ident, ty) = (&f.member, f.ty);
+        match &f.kind {
+            Kind::Column | Kind::Default => quote! {
+                tuple.serialize_element(&::trino_rust_client::types::Trino::value(&self.#ident))?;
+            },
+            Kind::With { module, .. } => quote! {
+                {
+                    struct __SerializeWith #with_impl_generics #where_clause {
+                        value: &'_w #ty,
+                        _marker: ::std::marker::PhantomData<#name #ty_generics>,
+                    }
+
+                    impl #with_impl_generics ::serde::Serialize for __SerializeWith #with_ty_generics #where_clause {
+                        fn serialize<_S: ::serde::Serializer>(&self, serializer: _S) -> ::std::result::Result<_S::Ok, _S::Error> {
+                            #module::serialize(self.value, serializer)
+                        }
+                    }
+
+                    tuple.serialize_element(&__SerializeWith {
+                        value: &self.#ident,
+                        _marker: ::std::marker::PhantomData::<#name #ty_generics>,
+                    })?;
+                }
+            },
+            Kind::Flatten => quote! {
+                ::trino_rust_client::types::TrinoFields::serialize_fields(&self.#ident, tuple)?;
+            },
+            Kind::Skip => unreachable!(),
+        }
+    });
+
+    let mut offset = quote!(0usize);
+    let mut access = vec![];
+    let mut type_names = vec![];
+    for f in columns {
+        let (ident, ty) = (&f.member, f.ty);
+        let width = f.width();
+        let no_data = quote! {
+            Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data"))
+        };
+        access.push(match &f.kind {
+            Kind::Column | Kind::Default => quote! {
+                if idx == #offset {
+                    let seed = <#ty as ::trino_rust_client::types::Trino>::seed(ctx);
+                    return match seq.next_element_seed(seed)? {
+                        Some(data) => {
+                            self.#ident = data;
+                            Ok(())
+                        }
+                        None => #no_data,
+                    };
+                }
+            },
+            Kind::With { module, .. } => quote! {
+                if idx == #offset {
+                    struct __DeserializeWith #impl_generics #where_clause {
+                        value: #ty,
+                        _marker: ::std::marker::PhantomData<#name #ty_generics>,
+                    }
+
+                    impl #with_de_impl_generics ::serde::Deserialize<'_wde> for __DeserializeWith #ty_generics #where_clause {
+                        fn deserialize<_D: ::serde::Deserializer<'_wde>>(deserializer: _D) -> ::std::result::Result<Self, _D::Error> {
+                            Ok(__DeserializeWith {
+                                value: #module::deserialize(deserializer)?,
+                                _marker: ::std::marker::PhantomData,
+                            })
+                        }
+                    }
+
+                    return match seq.next_element::<__DeserializeWith #ty_generics>()? {
+                        Some(data) => {
+                            self.#ident = data.value;
+                            Ok(())
+                        }
+                        None => #no_data,
+                    };
+                }
+            },
+            Kind::Flatten => quote! {
+                if let Some(idx) = idx.checked_sub(#offset).filter(|idx| *idx < #width) {
+                    return ::trino_rust_client::types::TrinoFields::access_seq(&mut self.#ident, idx, seq, ctx);
+                }
+            },
+            Kind::Skip => unreachable!(),
+        });
+        type_names.push(match &f.kind {
+            Kind::Flatten => quote! {
+                if let Some(idx) = idx.checked_sub(#offset).filter(|idx| *idx < #width) {
+                    return <#ty as ::trino_rust_client::types::TrinoFields>::type_name(idx);
+                }
+            },
+            _ => quote! {
+                if idx == #offset {
+                    return ::std::any::type_name::<#ty>();
+                }
+            },
+        });
+        offset = quote!(#offset + #width);
+    }
+
+    let ret = quote! {
+        impl #impl_generics ::trino_rust_client::types::TrinoFields for #name #ty_generics #where_clause {
+            const WIDTH: usize = 0usize #( + #width)*;
+
+            fn fields() -> ::std::vec::Vec<(::std::string::String, ::trino_rust_client::types::TrinoTy)> {
+                let mut fields = ::std::vec::Vec::new();
+                #( #fields )*
+                fields
+            }
+
+            fn serialize_fields<_S: ::serde::ser::SerializeTuple>(&self, tuple: &mut _S) -> ::std::result::Result<(), _S::Error> {
+                #( #serialize )*
+                Ok(())
+            }
+
+            fn access_seq<'_a, '_de, _A: ::serde::de::SeqAccess<'_de>>(&mut self, idx: usize, seq: &mut _A, ctx: &'_a ::trino_rust_client::types::Context<'_a>)
                 -> ::std::result::Result<(), _A::Error> {
-                match idx {
-                    #(
-                        #indices => {
-                            let seed = <#types as ::trino_rust_client::types::Trino>::seed(ctx);
-                            let data = seq.next_element_seed(seed)?;
-                            if let Some(data) = data {
-                                self.#keys = data;
-                                Ok(())
-                            } else {
-                                Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data"))
-                            }
-                        },
-                    )*
-                    _ => unreachable!(),
+                #( #access )*
+
+                // a column without field, ignored by a lenient struct
+                match seq.next_element::<::serde::de::IgnoredAny>()? {
+                    Some(_) => Ok(()),
+                    None => Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data")),
+                }
+            }
+
+            fn type_name(idx: usize) -> &'static str {
+                #( #type_names )*
+
+                ::std::any::type_name::<::serde::de::IgnoredAny>()
+            }
+        }
+    };
+
+    Ok(ret)
+}
+
+fn derive_enum(data: ItemEnum) -> Result<TokenStream> {
+    let name = &data.ident;
+    let vis = &data.vis;
+    let seed_name = format_ident!("__{}Seed", name);
+    if !data.generics.params.is_empty() {
+        return Err(Error::new(data.generics.span(), "enum can not be generic"));
+    }
+    if data.variants.is_empty() {
+        return Err(Error::new(data.span(), "enum can not be empty"));
+    }
+
+    let rename_all = match parse_attrs::<ContainerAttributes>(&data.attrs)? {
+        Some(attrs) => {
+            if let Some(span) = attrs.lenient.span {
+                return Err(Error::new(span, "lenient is not supported on enums"));
+            }
+            attrs
+                .rename_all
+                .as_ref()
+                .map(RenameRule::parse)
+                .transpose()?
+        }
+        None => None,
+    };
+
+    let mut idents = vec![];
+    let mut names = vec![];
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.span(), "variant must be a unit variant"));
+        }
+        let ident = &variant.ident;
+        let mut name = ident.unraw().to_string();
+        if let Some(rule) = rename_all {
+            name = rule.apply_to_variant(&name);
+        }
+        if let Some(attrs) = parse_attrs::<FieldAttributes>(&variant.attrs)? {
+            let unsupported = [
+                attrs.default.span,
+                attrs.skip.span,
+                attrs.flatten.span,
+                attrs.with.as_ref().map(|w| w.span()),
+            ];
+            if let Some(span) = unsupported.into_iter().flatten().next() {
+                return Err(Error::new(span, "variants only support rename"));
+            }
+            if let Some(rename) = attrs.rename {
+                name = rename.value();
+            }
+        }
+        idents.push(ident);
+        names.push(LitStr::new(&name, ident.span()));
+    }
+    let first = idents[0];
+
+    let ret = quote! {
+        impl ::trino_rust_client::types::Trino for #name {
+            type ValueType<'_a> = &'static str;
+            type Seed<'_a, '_de> = #seed_name;
+
+            fn value(&self) -> Self::ValueType<'_>  {
+                match self {
+                    #( Self::#idents => #names, )*
+                }
+            }
+
+            fn ty() -> ::trino_rust_client::types::TrinoTy {
+                ::trino_rust_client::types::TrinoTy::Varchar(None)
+            }
+
+            fn seed<'_a, '_de>(_ctx: &'_a ::trino_rust_client::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
+                #seed_name
+            }
+
+            fn empty() -> Self {
+                Self::#first
+            }
+        }
+
+        impl ::trino_rust_client::types::TrinoMapKey for #name {}
+
+        #vis struct #seed_name;
+
+        impl<'_de> ::serde::de::DeserializeSeed<'_de> for #seed_name {
+            type Value = #name;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<'_de>,
+            {
+                let s = <::std::string::String as ::serde::Deserialize>::deserialize(deserializer)?;
+                match s.as_str() {
+                    #( #names => Ok(#name::#idents), )*
+                    other => Err(<D::Error as ::serde::de::Error>::unknown_variant(other, &[ #(#names),* ])),
                 }
             }
         }
     };
 
-    Ok(access_seq)
+    Ok(ret)
+}
+
+/// The `rename_all` rules, as in serde.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> Result<Self> {
+        use RenameRule::*;
+
+        Ok(match lit.value().as_str() {
+            "lowercase" => Lower,
+            "UPPERCASE" => Upper,
+            "PascalCase" => Pascal,
+            "camelCase" => Camel,
+            "snake_case" => Snake,
+            "SCREAMING_SNAKE_CASE" => ScreamingSnake,
+            "kebab-case" => Kebab,
+            "SCREAMING-KEBAB-CASE" => ScreamingKebab,
+            _ => return Err(Error::new(lit.span(), "unknown rename rule")),
+        })
+    }
+
+    /// Rename a PascalCase variant name.
+    fn apply_to_variant(self, variant: &str) -> String {
+        use RenameRule::*;
+
+        match self {
+            Lower => variant.to_ascii_lowercase(),
+            Upper => variant.to_ascii_uppercase(),
+            Pascal => variant.to_owned(),
+            Camel => match variant.char_indices().nth(1) {
+                Some((i, _)) => variant[..i].to_lowercase() + &variant[i..],
+                None => variant.to_lowercase(),
+            },
+            Snake | ScreamingSnake | Kebab | ScreamingKebab => {
+                let mut snake = String::new();
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                self.apply(&snake)
+            }
+        }
+    }
+
+    /// Rename a snake_case field name.
+    fn apply(self, field: &str) -> String {
+        use RenameRule::*;
+
+        match self {
+            Lower | Snake => field.to_owned(),
+            Upper | ScreamingSnake => field.to_ascii_uppercase(),
+            Pascal | Camel => {
+                let mut ret = String::new();
+                let mut upper = matches!(self, Pascal);
+                for c in field.chars() {
+                    if c == '_' {
+                        upper = true;
+                    } else if upper {
+                        ret.push(c.to_ascii_uppercase());
+                        upper = false;
+                    } else {
+                        ret.push(c);
+                    }
+                }
+                ret
+            }
+            Kebab => field.replace('_', "-"),
+            ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
 }